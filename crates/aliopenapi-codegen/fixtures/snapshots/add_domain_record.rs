@@ -0,0 +1,39 @@
+use api_derive::Api;
+use serde::Deserialize;
+use serde::Serialize;
+
+///添加解析记录
+///
+///根据传入参数添加解析记录。
+#[derive(Debug, Clone, Api)]
+pub struct AddDomainRecord {
+    ///语言类型，默认为 zh。
+    #[api(name = "Lang")]
+    lang: Option<String>,
+    ///必填。域名名称。
+    #[api(name = "DomainName", required)]
+    domain_name: Option<String>,
+    ///必填。主机记录。
+    ///
+    ///如果要解析@.example.com，主机记录要填写”@”，而不是空。
+    #[api(name = "RR", required)]
+    rr: Option<String>,
+    ///必填。解析记录类型，参见解析记录类型格式。
+    #[api(name = "Type", required)]
+    r#type: Option<String>,
+    ///必填。记录值。
+    #[api(name = "Value", required)]
+    value: Option<String>,
+    ///解析生效时间，默认为 600 秒（10 分钟）。
+    #[api(name = "TTL")]
+    ttl: Option<i64>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct AddDomainRecordResponse {
+    ///唯一请求识别码。
+    pub request_id: String,
+    ///解析记录的 ID。
+    pub record_id: String,
+}
@@ -0,0 +1,8 @@
+const VERSION: &str = "2015-01-09";
+const PRODUCT: &str = "Alidns";
+
+pub mod add_domain_record;
+pub use add_domain_record::{AddDomainRecord, AddDomainRecordResponse};
+
+pub mod describe_domain_records;
+pub use describe_domain_records::{DescribeDomainRecords, DescribeDomainRecordsResponse};
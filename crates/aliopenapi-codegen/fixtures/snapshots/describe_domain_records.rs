@@ -0,0 +1,70 @@
+use api_derive::Api;
+use serde::Deserialize;
+use serde::Serialize;
+
+///获取解析记录列表
+///
+///根据传入参数获取指定主域名的所有解析记录列表。
+#[derive(Debug, Clone, Api)]
+pub struct DescribeDomainRecords {
+    ///必填。域名名称。
+    #[api(name = "DomainName", required)]
+    domain_name: Option<String>,
+    ///当前页数，起始值为 1，默认为 1。
+    #[api(name = "PageNumber")]
+    page_number: Option<i64>,
+    ///分页查询时设置的每页行数，最大值 500，默认为 20。
+    #[api(name = "PageSize")]
+    page_size: Option<i64>,
+    ///解析记录状态。取值：
+    ///Enable：启用解析
+    ///Disable：暂停解析
+    #[api(name = "Status")]
+    status: Option<String>,
+    ///排序方向。取值：DESC、ASC。
+    #[api(name = "Direction")]
+    direction: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct DescribeDomainRecordsResponse {
+    ///解析记录总数。
+    pub total_count: i64,
+    ///本次查询的每页行数。
+    pub page_size: i64,
+    ///唯一请求识别码。
+    pub request_id: String,
+    ///当前页码。
+    pub page_number: i64,
+    ///解析记录列表。
+    pub domain_records: DomainRecords,
+}
+
+///解析记录列表。
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct DomainRecords {
+    pub record: Vec<Record>,
+}
+
+///解析记录。
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct Record {
+    ///解析记录 ID。
+    pub record_id: String,
+    ///主机记录。
+    #[serde(rename = "RR")]
+    pub rr: String,
+    ///记录类型。
+    #[serde(rename = "Type")]
+    pub type_field: String,
+    ///生存时间。
+    #[serde(rename = "TTL")]
+    pub ttl: i64,
+    ///当前解析记录锁定状态。
+    pub locked: bool,
+    ///负载均衡权重。
+    pub weight: i64,
+}
@@ -0,0 +1,71 @@
+use api_derive::Api;
+use serde::Deserialize;
+use serde::Serialize;
+
+///查询短信发送统计信息
+///
+///查看短信发送统计详情，包括短信发送时间、短信发送成功条数、接收回执条数等。
+#[derive(Debug, Clone, Api)]
+#[api(method = "POST", body = "form")]
+pub struct QuerySendStatistics {
+    ///必填。短信发送范围。取值：
+    ///
+    ///1：国内短信发送记录。
+    ///
+    ///2：国际/港澳台短信发送记录。
+    #[api(name = "IsGlobe", required)]
+    is_globe: Option<i64>,
+    ///必填。开始日期，格式为 yyyyMMdd，例如 20181225。
+    #[api(name = "StartDate", required)]
+    start_date: Option<String>,
+    ///必填。结束日期，格式为 yyyyMMdd，例如 20181225。
+    #[api(name = "EndDate", required)]
+    end_date: Option<String>,
+    ///必填。页码。默认取值为 1。
+    #[api(name = "PageIndex", required)]
+    page_index: Option<i64>,
+    ///短信签名。
+    #[api(name = "SignName")]
+    sign_name: Option<String>,
+    ///短信模板 Code 列表。
+    ///JSON 格式。
+    #[api(name = "TemplateCodes")]
+    template_codes: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct QuerySendStatisticsResponse {
+    ///请求状态码。
+    pub code: String,
+    ///状态码的描述。
+    pub message: String,
+    ///请求 ID。
+    pub request_id: String,
+    ///返回数据。
+    pub data: Data,
+}
+
+///返回数据。
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct Data {
+    ///返回数据的总条数。
+    pub total_size: i64,
+    ///返回数据列表。
+    pub target_list: Vec<TargetList>,
+    ///附加信息。
+    pub extra: serde_json::Value,
+}
+
+///每天的发送统计。
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct TargetList {
+    ///发送日期，格式为 yyyyMMdd。
+    pub send_date: String,
+    ///发送成功的短信条数。
+    pub total_count: i64,
+    ///接收到回执成功的短信条数。
+    pub responded_success_count: i64,
+}
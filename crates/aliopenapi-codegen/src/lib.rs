@@ -0,0 +1,78 @@
+//! 根据阿里云 OpenAPI 元数据（api-docs 格式的 JSON）生成 `src/aliapis/<product>/<action>.rs`
+//!
+//! 生成的模块包含派生 `Api` 的请求结构体、参数文档和返回的结构体，只支持 RPC 风格的接口
+pub mod metadata;
+pub mod render;
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+pub use metadata::ApiDocs;
+pub use render::{render_api, render_mod, render_mod_entry, snake_case};
+
+/// 读取本地的元数据文件
+pub fn load(path: impl AsRef<Path>) -> Result<ApiDocs> {
+    let path = path.as_ref();
+    let content =
+        std::fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("parse {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{load, render_api, render_mod, snake_case, ApiDocs};
+
+    fn fixture(name: &str) -> ApiDocs {
+        load(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("fixtures")
+                .join(name),
+        )
+        .unwrap()
+    }
+
+    // 与 fixtures/snapshots 中的文件比较，设置 UPDATE_SNAPSHOTS 时更新快照；
+    // 快照还会在主 crate 的 codegen_snapshots 模块中与 derive(Api) 一起编译
+    fn assert_snapshot(name: &str, output: &str) {
+        syn::parse_file(output).unwrap();
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures/snapshots")
+            .join(name);
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(&path, output).unwrap();
+        }
+        let expected = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(output, expected, "snapshot {} changed", name);
+    }
+
+    #[test]
+    fn alidns() {
+        let docs = fixture("alidns.json");
+        for action in ["AddDomainRecord", "DescribeDomainRecords"] {
+            let output = render_api(&docs, action).unwrap();
+            assert_snapshot(&format!("{}.rs", snake_case(action)), &output);
+        }
+        let actions: Vec<_> = docs.apis.keys().cloned().collect();
+        assert_snapshot("alidns_mod.rs", &render_mod(&docs, &actions));
+    }
+
+    #[test]
+    fn form_body() {
+        let docs = fixture("dysmsapi.json");
+        let output = render_api(&docs, "QuerySendStatistics").unwrap();
+        assert_snapshot("query_send_statistics.rs", &output);
+    }
+
+    #[test]
+    fn unsupported() {
+        let mut docs = fixture("dysmsapi.json");
+        assert!(render_api(&docs, "NotExist").is_err());
+        let api = docs.apis.get_mut("QuerySendStatistics").unwrap();
+        api.parameters[0].location = "path".to_string();
+        let error = render_api(&docs, "QuerySendStatistics").unwrap_err();
+        assert!(error.to_string().contains("in path is not supported"));
+    }
+}
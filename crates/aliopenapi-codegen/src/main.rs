@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+
+use aliopenapi_codegen::{load, render_api, render_mod, render_mod_entry, snake_case};
+
+const USAGE: &str =
+    "usage: aliopenapi-codegen <api-docs.json> [--out <dir>] [--module <name>] [--api <Action>]...
+
+  --out     生成的模块所在的目录，默认为 src/aliapis
+  --module  产品模块名，默认为小写的产品代码
+  --api     只生成指定的接口，可以重复，默认生成所有接口";
+
+fn main() -> Result<()> {
+    let mut input = None;
+    let mut out = PathBuf::from("src/aliapis");
+    let mut module = None;
+    let mut actions = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => out = args.next().context(USAGE)?.into(),
+            "--module" => module = Some(args.next().context(USAGE)?),
+            "--api" => actions.push(args.next().context(USAGE)?),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ if input.is_none() && !arg.starts_with('-') => input = Some(arg),
+            _ => bail!("unexpected argument {}\n\n{}", arg, USAGE),
+        }
+    }
+    let input = input.context(USAGE)?;
+
+    let docs = load(&input)?;
+    if actions.is_empty() {
+        actions = docs.apis.keys().cloned().collect();
+    }
+    let module = module.unwrap_or_else(|| docs.info.product.to_lowercase());
+    let dir = out.join(&module);
+    std::fs::create_dir_all(&dir).with_context(|| format!("create {}", dir.display()))?;
+
+    for action in &actions {
+        let path = dir.join(format!("{}.rs", snake_case(action)));
+        std::fs::write(&path, render_api(&docs, action)?)
+            .with_context(|| format!("write {}", path.display()))?;
+        println!("generated {}", path.display());
+    }
+
+    // 已有的 mod.rs 不覆盖，只提示需要添加的声明
+    let mod_path = dir.join("mod.rs");
+    if mod_path.exists() {
+        println!("\nadd to {}:\n", mod_path.display());
+        for action in &actions {
+            print!("{}", render_mod_entry(action));
+        }
+    } else {
+        std::fs::write(&mod_path, render_mod(&docs, &actions))
+            .with_context(|| format!("write {}", mod_path.display()))?;
+        println!("generated {}", mod_path.display());
+        println!(
+            "\nadd to {}:\n\n#[cfg(feature = \"{module}\")]\npub mod {module};\n",
+            out.join("mod.rs").display()
+        );
+        println!("add to Cargo.toml [features]:\n\n{module} = []");
+    }
+    Ok(())
+}
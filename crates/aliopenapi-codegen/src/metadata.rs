@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+
+use ordermap::OrderMap;
+use serde::Deserialize;
+
+/// 阿里云 OpenAPI 元数据（api-docs 格式）中生成代码用到的部分
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ApiDocs {
+    pub info: Info,
+    pub components: Components,
+    pub apis: OrderMap<String, ApiDoc>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Info {
+    pub product: String,
+    pub version: String,
+    pub style: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Components {
+    pub schemas: BTreeMap<String, Schema>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ApiDoc {
+    pub summary: String,
+    pub title: String,
+    pub methods: Vec<String>,
+    pub deprecated: bool,
+    pub parameters: Vec<Parameter>,
+    pub responses: BTreeMap<String, ApiResponse>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Parameter {
+    pub name: String,
+    #[serde(rename = "in")]
+    pub location: String,
+    pub schema: Schema,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ApiResponse {
+    pub schema: Option<Schema>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Schema {
+    #[serde(rename = "type")]
+    pub schema_type: String,
+    pub format: String,
+    pub title: String,
+    pub description: String,
+    /// 参数中为布尔值，对象中为必填属性的列表
+    pub required: serde_json::Value,
+    pub properties: OrderMap<String, Schema>,
+    pub items: Option<Box<Schema>>,
+    #[serde(rename = "$ref")]
+    pub reference: Option<String>,
+    #[serde(rename = "enum")]
+    pub enumeration: Vec<serde_json::Value>,
+    pub example: serde_json::Value,
+}
+
+impl Schema {
+    /// 参数是否必填
+    pub fn is_required(&self) -> bool {
+        self.required.as_bool().unwrap_or(false)
+    }
+}
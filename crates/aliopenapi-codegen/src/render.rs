@@ -0,0 +1,402 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Write;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::metadata::{ApiDoc, ApiDocs, Schema};
+
+// Rust 关键字，请求参数使用原始标识符，返回字段加 `_field` 后缀
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+// 不能作为原始标识符的关键字
+const NON_RAW: &[&str] = &["crate", "self", "super"];
+
+const DERIVE_RESPONSE: &str =
+    "#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]\n\
+                               #[serde(rename_all = \"PascalCase\", default)]\n";
+
+/// 生成一个接口的模块，包含请求结构体、参数 setter 的文档和返回的结构体
+pub fn render_api(docs: &ApiDocs, action: &str) -> Result<String> {
+    let api = docs
+        .apis
+        .get(action)
+        .ok_or_else(|| anyhow!("api {} not found in metadata", action))?;
+    if docs.info.style.eq_ignore_ascii_case("ROA") {
+        bail!("{}: ROA style apis are not supported", action);
+    }
+
+    let mut output =
+        String::from("use api_derive::Api;\nuse serde::Deserialize;\nuse serde::Serialize;\n\n");
+    render_request(&mut output, api, action)?;
+
+    let mut models = Models::new(docs, action);
+    let schema = api
+        .responses
+        .get("200")
+        .and_then(|response| response.schema.clone())
+        .unwrap_or_default();
+    // 返回的根结构体不使用元数据中的 "Schema of Response" 作为文档
+    let schema = Schema {
+        title: String::new(),
+        description: String::new(),
+        ..models.resolve(&schema)?
+    };
+    let root = models.reserve(&format!("{}Response", action));
+    models.pending.push_back((root, schema));
+    while let Some((name, schema)) = models.pending.pop_front() {
+        output.push('\n');
+        models.render_struct(&mut output, &name, &schema)?;
+    }
+    Ok(output)
+}
+
+/// 生成产品的 mod.rs，`VERSION` 和 `PRODUCT` 供派生的 `Api` 使用
+pub fn render_mod(docs: &ApiDocs, actions: &[String]) -> String {
+    let mut output = format!(
+        "const VERSION: &str = \"{}\";\nconst PRODUCT: &str = \"{}\";\n",
+        docs.info.version, docs.info.product
+    );
+    for action in actions {
+        output.push('\n');
+        output.push_str(&render_mod_entry(action));
+    }
+    output
+}
+
+/// mod.rs 中一个接口的声明和导出
+pub fn render_mod_entry(action: &str) -> String {
+    format!(
+        "pub mod {module};\npub use {module}::{{{action}, {action}Response}};\n",
+        module = snake_case(action),
+    )
+}
+
+fn render_request(output: &mut String, api: &ApiDoc, action: &str) -> Result<()> {
+    let mut form = false;
+    let mut fields = String::new();
+    for parameter in &api.parameters {
+        match parameter.location.as_str() {
+            "query" => {}
+            "formData" => form = true,
+            location => bail!(
+                "{}: parameter {} in {} is not supported",
+                action,
+                parameter.name,
+                location
+            ),
+        }
+        let schema = &parameter.schema;
+        let (ty, json) = match schema.schema_type.as_str() {
+            "integer" => ("i64", false),
+            "number" => ("f64", false),
+            "boolean" => ("bool", false),
+            "array" | "object" => ("String", true),
+            _ => ("String", false),
+        };
+
+        let mut lines = doc_lines(description(schema));
+        if schema.is_required() {
+            match lines.first_mut() {
+                Some(first) => first.insert_str(0, "必填。"),
+                None => lines.push("必填。".to_string()),
+            }
+        }
+        if json {
+            lines.push("JSON 格式。".to_string());
+        }
+        push_docs(&mut fields, "    ", &lines);
+        let required = if schema.is_required() {
+            ", required"
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            fields,
+            "    #[api(name = \"{}\"{})]",
+            parameter.name, required
+        );
+        let _ = writeln!(
+            fields,
+            "    {}: Option<{}>,",
+            request_field(&parameter.name),
+            ty
+        );
+    }
+
+    let mut lines = doc_lines(&api.title);
+    if !api.summary.is_empty() && api.summary != api.title {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.extend(doc_lines(&api.summary));
+    }
+    if api.deprecated {
+        lines.push("已废弃。".to_string());
+    }
+    push_docs(output, "", &lines);
+    output.push_str("#[derive(Debug, Clone, Api)]\n");
+
+    let mut attributes = Vec::new();
+    let get = api
+        .methods
+        .iter()
+        .any(|method| method.eq_ignore_ascii_case("get"));
+    if form || !(get || api.methods.is_empty()) {
+        attributes.push("method = \"POST\"".to_string());
+    }
+    if form {
+        attributes.push("body = \"form\"".to_string());
+    }
+    if !attributes.is_empty() {
+        let _ = writeln!(output, "#[api({})]", attributes.join(", "));
+    }
+    if fields.is_empty() {
+        let _ = writeln!(output, "pub struct {} {{}}", action);
+    } else {
+        let _ = write!(output, "pub struct {} {{\n{}}}\n", action, fields);
+    }
+    Ok(())
+}
+
+// 返回的结构体，按出现的顺序命名和生成
+struct Models<'a> {
+    docs: &'a ApiDocs,
+    names: BTreeSet<String>,
+    references: BTreeMap<String, String>,
+    pending: VecDeque<(String, Schema)>,
+}
+
+impl<'a> Models<'a> {
+    fn new(docs: &'a ApiDocs, action: &str) -> Self {
+        Self {
+            docs,
+            names: BTreeSet::from([action.to_string()]),
+            references: BTreeMap::new(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    // 同名的结构体加数字后缀
+    fn reserve(&mut self, name: &str) -> String {
+        let mut candidate = name.to_string();
+        let mut suffix = 2;
+        while !self.names.insert(candidate.clone()) {
+            candidate = format!("{}{}", name, suffix);
+            suffix += 1;
+        }
+        candidate
+    }
+
+    fn resolve(&self, schema: &Schema) -> Result<Schema> {
+        let Some(reference) = &schema.reference else {
+            return Ok(schema.clone());
+        };
+        let name = reference.rsplit('/').next().unwrap_or_default();
+        let mut resolved = self
+            .docs
+            .components
+            .schemas
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("schema {} not found in components", reference))?;
+        if resolved.description.is_empty() {
+            resolved.description = schema.description.clone();
+        }
+        Ok(resolved)
+    }
+
+    fn rust_type(&mut self, name: &str, schema: &Schema) -> Result<String> {
+        if let Some(reference) = &schema.reference {
+            if let Some(name) = self.references.get(reference) {
+                return Ok(name.clone());
+            }
+            let resolved = self.resolve(schema)?;
+            if resolved.schema_type == "object" && !resolved.properties.is_empty() {
+                let name = self.reserve(&type_name(name));
+                self.references.insert(reference.clone(), name.clone());
+                self.pending.push_back((name.clone(), resolved));
+                return Ok(name);
+            }
+            return self.rust_type(name, &resolved);
+        }
+        Ok(match schema.schema_type.as_str() {
+            "integer" => "i64".to_string(),
+            "number" => "f64".to_string(),
+            "boolean" => "bool".to_string(),
+            "array" => {
+                let items = schema.items.as_deref().cloned().unwrap_or_default();
+                format!("Vec<{}>", self.rust_type(&singular(name), &items)?)
+            }
+            "object" if schema.properties.is_empty() => "serde_json::Value".to_string(),
+            "object" => {
+                let name = self.reserve(&type_name(name));
+                self.pending.push_back((name.clone(), schema.clone()));
+                name
+            }
+            _ => "String".to_string(),
+        })
+    }
+
+    fn render_struct(&mut self, output: &mut String, name: &str, schema: &Schema) -> Result<()> {
+        push_docs(output, "", &doc_lines(description(schema)));
+        output.push_str(DERIVE_RESPONSE);
+        if schema.properties.is_empty() {
+            let _ = writeln!(output, "pub struct {} {{}}", name);
+            return Ok(());
+        }
+        let _ = writeln!(output, "pub struct {} {{", name);
+        for (property, field) in &schema.properties {
+            let ty = self.rust_type(property, field)?;
+            let description = match &field.reference {
+                Some(_) if field.description.is_empty() => self.resolve(field)?.description,
+                _ => description(field).to_string(),
+            };
+            push_docs(output, "    ", &doc_lines(&description));
+            let ident = response_field(property);
+            if pascal_case(&ident) != *property {
+                let _ = writeln!(output, "    #[serde(rename = \"{}\")]", property);
+            }
+            let _ = writeln!(output, "    pub {}: {},", ident, ty);
+        }
+        output.push_str("}\n");
+        Ok(())
+    }
+}
+
+fn description(schema: &Schema) -> &str {
+    if schema.description.is_empty() {
+        &schema.title
+    } else {
+        &schema.description
+    }
+}
+
+fn doc_lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = text
+        .trim()
+        .lines()
+        .map(|line| line.trim_end().to_string())
+        .collect();
+    lines.dedup_by(|line, previous| line.is_empty() && previous.is_empty());
+    lines
+}
+
+fn push_docs(output: &mut String, indent: &str, lines: &[String]) {
+    for line in lines {
+        let _ = writeln!(output, "{}///{}", indent, line);
+    }
+}
+
+/// 把参数名或接口名转换为 snake_case，连续的大写字母视为一个词
+pub fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut output = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !output.is_empty() && !output.ends_with('_') {
+                output.push('_');
+            }
+            continue;
+        }
+        if c.is_ascii_uppercase() && i > 0 && !output.is_empty() && !output.ends_with('_') {
+            let previous = chars[i - 1];
+            let next_lower = chars
+                .get(i + 1)
+                .is_some_and(|next| next.is_ascii_lowercase());
+            if previous.is_ascii_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_ascii_uppercase() && next_lower)
+            {
+                output.push('_');
+            }
+        }
+        output.push(c.to_ascii_lowercase());
+    }
+    let output = output.trim_end_matches('_');
+    match output.chars().next() {
+        None => "field".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("n{}", output),
+        Some(_) => output.to_string(),
+    }
+}
+
+// 与 serde 的 `rename_all = "PascalCase"` 相同的转换，用于判断是否需要 rename
+fn pascal_case(field: &str) -> String {
+    field
+        .trim_start_matches("r#")
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn request_field(name: &str) -> String {
+    let field = snake_case(name);
+    if NON_RAW.contains(&field.as_str()) {
+        format!("{}_", field)
+    } else if KEYWORDS.contains(&field.as_str()) {
+        format!("r#{}", field)
+    } else {
+        field
+    }
+}
+
+fn response_field(name: &str) -> String {
+    let field = snake_case(name);
+    if KEYWORDS.contains(&field.as_str()) {
+        format!("{}_field", field)
+    } else {
+        field
+    }
+}
+
+fn type_name(property: &str) -> String {
+    let name = pascal_case(&snake_case(property));
+    if KEYWORDS.contains(&name.to_lowercase().as_str()) {
+        format!("{}Item", name)
+    } else {
+        name
+    }
+}
+
+// 数组的元素类型，去掉名称末尾的复数 s
+fn singular(name: &str) -> String {
+    match name.strip_suffix('s') {
+        Some(stem) if stem.len() > 1 && !stem.ends_with('s') => stem.to_string(),
+        _ => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pascal_case, singular, snake_case};
+
+    #[test]
+    fn names() {
+        assert_eq!(
+            snake_case("DescribeDomainRecords"),
+            "describe_domain_records"
+        );
+        assert_eq!(snake_case("RR"), "rr");
+        assert_eq!(snake_case("DNSSpeed"), "dns_speed");
+        assert_eq!(snake_case("ipIsp"), "ip_isp");
+        assert_eq!(snake_case("__time__"), "time");
+        assert_eq!(snake_case("Tag.1.Key"), "tag_1_key");
+        assert_eq!(snake_case("Ipv4Address"), "ipv4_address");
+        assert_eq!(pascal_case("type_field"), "TypeField");
+        assert_eq!(pascal_case("r#type"), "Type");
+        assert_eq!(singular("Items"), "Item");
+        assert_eq!(singular("Record"), "Record");
+        assert_eq!(singular("Address"), "Address");
+    }
+}
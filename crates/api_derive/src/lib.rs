@@ -1,39 +1,469 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
 
-/// Derive macro generating an impl of the trait `Api`
-#[proc_macro_derive(Api)]
+/// Derive macro generating an impl of the trait `Api` and typed setters for a struct
+/// whose named fields are the request parameters
+///
+/// Every parameter field is an `Option<T>` annotated with its wire name. A setter
+/// `set_<field>(impl Into<T>)` carrying the field's doc comment is generated for it (numeric
+/// fields take `T` itself so that integer literals are not inferred as `i32`), and the value is
+/// serialized with `ParamValue` when the request is built:
+///
+/// ```ignore
+/// #[derive(Debug, Clone, Api)]
+/// #[api(default(PageSize = 500))]
+/// pub struct DescribeDomainRecords {
+///     /// 域名
+///     #[api(name = "DomainName")]
+///     domain_name: Option<String>,
+///     #[api(name = "PageSize")]
+///     page_size: Option<i64>,
+/// }
+/// ```
+///
+/// Supported field attributes:
+///
+/// - `name = "PageSize"`: the wire name, required for parameter fields
+/// - `setter = false`: do not generate the setter, e.g. when it is written by hand
+/// - `setter = "set_record_type"`: rename the setter
+/// - `required`: `validate()` reports the parameter when it is not set or serializes to an
+///   empty string
+/// - `format = "%Y%m%d"`: serialize a date or time with `FormatParam` instead of `ParamValue`,
+///   either a strftime format or `"seconds"` / `"millis"` for Unix timestamps
+/// - `offset = "+08:00"`: the time zone a `DateTime<Utc>` is converted to before formatting,
+///   defaults to UTC
+/// - `region`: the region parameter, e.g. `RegionId`; when it is not set the client fills it
+///   with its own region, `cn-hangzhou` outside a client. Only for query parameters
+/// - `skip`: not a request parameter, initialized with `Default::default()`
+///
+/// Supported struct attributes, all optional:
+///
+/// - `action = "DescribeDomains"`: the `x-acs-action`, defaults to the struct name
+/// - `version = "2015-01-09"`: defaults to `super::VERSION`
+/// - `product = "Alidns"`: the product code used to resolve the host, defaults to `super::PRODUCT`
+/// - `endpoint = "alidns.aliyuncs.com"`: send to a fixed host instead of resolving by product
+/// - `method = "POST"`: defaults to GET
+/// - `body = "form"`: send the parameters as a form body instead of the query string
+/// - `idempotent = false`: only retry when the connection fails; `true` is the default
+/// - `default(PageSize = 500, RegionId = "cn-hangzhou")`: parameters sent when the field is not set
+/// - `response = "path::to::Response"`: defaults to `<StructName>Response`
+/// - `signature = "v2"`: APIs that only accept the legacy RPC signature
+/// - `validate_with = "check_billing_cycle"`: a method `fn(&self, &mut ValidationError)` called
+///   by `validate()` after the required parameters are checked, may be repeated
+#[proc_macro_derive(Api, attributes(api))]
 pub fn api_derive(input: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    match expand(input) {
+        Ok(expanded) => expanded.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+// 请求参数字段
+struct Parameter {
+    ident: syn::Ident,
+    name: String,
+    ty: syn::Type,
+    setter: Option<syn::Ident>,
+    required: bool,
+    // 未设置时由客户端按地域填充
+    region: bool,
+    // 日期、时间的格式和时区偏移的秒数
+    format: Option<(syn::LitStr, i32)>,
+    docs: Vec<syn::Attribute>,
+}
+
+fn expand(input: syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let struct_identifier = input.ident;
 
-    let expanded = quote::quote! {
-        use crate::sign::Api;
-        use crate::sign::RequestHeader;
-        
-        impl Api for #struct_identifier {
+    let mut action = struct_identifier.to_string();
+    let mut version = quote::quote!(super::VERSION);
+    let mut host = None;
+    let mut product = quote::quote!(super::PRODUCT);
+    let mut method = None;
+    let mut form_body = false;
+    let mut idempotent = true;
+    let mut defaults = Vec::new();
+    let mut response: syn::Type = syn::parse_str(&format!("{}Response", struct_identifier))?;
+    let mut signature_version = quote::quote!(crate::aliapis::sign::SignatureVersion::V3);
+    let mut validators: Vec<syn::Ident> = Vec::new();
+
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("api")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("action") {
+                action = meta.value()?.parse::<syn::LitStr>()?.value();
+            } else if meta.path.is_ident("version") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                version = quote::quote!(#value);
+            } else if meta.path.is_ident("product") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                product = quote::quote!(#value);
+            } else if meta.path.is_ident("endpoint") {
+                host = Some(meta.value()?.parse::<syn::LitStr>()?);
+            } else if meta.path.is_ident("method") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                match value.value().to_uppercase().as_str() {
+                    "GET" | "POST" | "PUT" | "DELETE" | "HEAD" | "PATCH" => method = Some(value),
+                    _ => return Err(meta.error("unsupported http method")),
+                }
+            } else if meta.path.is_ident("body") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                form_body = match value.value().as_str() {
+                    "form" => true,
+                    "query" => false,
+                    _ => return Err(meta.error("body must be \"form\" or \"query\"")),
+                };
+            } else if meta.path.is_ident("idempotent") {
+                idempotent = meta.value()?.parse::<syn::LitBool>()?.value;
+            } else if meta.path.is_ident("default") {
+                meta.parse_nested_meta(|parameter| {
+                    let key = parameter
+                        .path
+                        .get_ident()
+                        .ok_or_else(|| parameter.error("expected a parameter name"))?
+                        .to_string();
+                    let value = match parameter.value()?.parse::<syn::Lit>()? {
+                        syn::Lit::Str(value) => value.value(),
+                        syn::Lit::Int(value) => value.base10_digits().to_string(),
+                        syn::Lit::Bool(value) => value.value.to_string(),
+                        _ => return Err(parameter.error("expected a string, integer or bool")),
+                    };
+                    defaults.push((key, value));
+                    Ok(())
+                })?;
+            } else if meta.path.is_ident("response") {
+                response = meta.value()?.parse::<syn::LitStr>()?.parse()?;
+            } else if meta.path.is_ident("signature") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                signature_version = match value.value().as_str() {
+                    "v3" => quote::quote!(crate::aliapis::sign::SignatureVersion::V3),
+                    "v2" => quote::quote!(crate::aliapis::sign::SignatureVersion::RpcV2),
+                    _ => return Err(meta.error("signature must be \"v3\" or \"v2\"")),
+                };
+            } else if meta.path.is_ident("validate_with") {
+                validators.push(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+            } else {
+                return Err(meta.error("unsupported api attribute"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let fields = match input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => fields.named,
+        _ => {
+            return Err(syn::Error::new(
+                struct_identifier.span(),
+                "Api can only be derived for structs with named fields",
+            ))
+        }
+    };
+    let mut all_fields = Vec::new();
+    let mut parameters = Vec::new();
+    for field in fields {
+        let ident = field.ident.clone().expect("named field");
+        all_fields.push(ident.clone());
+        if let Some(parameter) = parse_field(field)? {
+            if parameter.required && defaults.iter().any(|(key, _)| *key == parameter.name) {
+                return Err(syn::Error::new(
+                    parameter.ident.span(),
+                    "a required parameter can not have a default value",
+                ));
+            }
+            if parameter.region && (parameter.required || form_body) {
+                return Err(syn::Error::new(
+                    parameter.ident.span(),
+                    "region can not be used with required or body = \"form\"",
+                ));
+            }
+            if parameter.region && defaults.iter().any(|(key, _)| *key == parameter.name) {
+                return Err(syn::Error::new(
+                    parameter.ident.span(),
+                    "a region parameter can not have a default value",
+                ));
+            }
+            parameters.push(parameter);
+        }
+    }
+
+    let setters = parameters.iter().filter_map(|parameter| {
+        let Parameter {
+            ident,
+            ty,
+            setter,
+            docs,
+            ..
+        } = parameter;
+        let setter = setter.as_ref()?;
+        let argument = ident;
+        // 参数接受可以转换为字段类型的值，例如字符串参数接受 &str，枚举参数接受原始字符串；
+        // 数字参数直接使用字段的类型，整数字面量不需要后缀也不会被推断为 i32
+        let (argument_type, value) = if is_number(ty) {
+            (quote::quote!(#ty), quote::quote!(#argument))
+        } else {
+            (
+                quote::quote!(impl Into<#ty>),
+                quote::quote!(#argument.into()),
+            )
+        };
+        Some(quote::quote! {
+            #(#docs)*
+            pub fn #setter(mut self, #argument: #argument_type) -> Self {
+                self.#ident = Some(#value);
+                self
+            }
+        })
+    });
+    let defaults = defaults.iter().map(|(key, value)| {
+        quote::quote! {
+            parameters.insert_sorted(#key.to_string(), #value.to_string());
+        }
+    });
+    let values = parameters.iter().map(
+        |Parameter {
+             ident,
+             name,
+             format,
+             ..
+         }| {
+            let value = match format {
+                Some((format, offset)) => quote::quote! {
+                    crate::aliapis::param::FormatParam::format_param(
+                        value,
+                        #format,
+                        crate::aliapis::param::utc_offset(#offset),
+                    )
+                },
+                None => quote::quote!(crate::aliapis::param::ParamValue::to_param(value)),
+            };
+            quote::quote! {
+                if let Some(value) = &self.#ident {
+                    parameters.insert_sorted(#name.to_string(), #value);
+                }
+            }
+        },
+    );
+
+    let required = parameters
+        .iter()
+        .filter(|parameter| parameter.required)
+        .map(|Parameter { ident, name, .. }| {
+            quote::quote! {
+                match &self.#ident {
+                    Some(value) if !crate::aliapis::param::ParamValue::to_param(value).is_empty() => {}
+                    _ => error.missing(#name),
+                }
+            }
+        });
+
+    let (query, body) = if form_body {
+        (
+            quote::quote!(::ordermap::OrderMap::new()),
+            quote::quote!(.set_body(parameters)),
+        )
+    } else {
+        (quote::quote!(parameters), quote::quote!())
+    };
+    let request = match host {
+        Some(host) => quote::quote! {
+            crate::aliapis::sign::RequestHeader::new(
+                #host.to_string(),
+                #action.to_string(),
+                #version.to_string(),
+                #query,
+            )
+        },
+        None => quote::quote! {
+            crate::aliapis::sign::RequestHeader::with_product(
+                #product,
+                #action.to_string(),
+                #version.to_string(),
+                #query,
+            )
+        },
+    };
+    let region = parameters.iter().filter(|parameter| parameter.region).map(
+        |Parameter { ident, name, .. }| {
+            quote::quote! {
+                let request = match &self.#ident {
+                    Some(_) => request,
+                    None => request.set_region_param(#name),
+                };
+            }
+        },
+    );
+    let method = method.map(|method| quote::quote!(.set_method(#method)));
+    let idempotent = (!idempotent).then(|| quote::quote!(.set_idempotent(false)));
+
+    Ok(quote::quote! {
+        impl #struct_identifier {
+            #(#setters)*
+
+            /// 请求参数，未设置的参数使用默认值
+            pub fn parameters(&self) -> ::ordermap::OrderMap<String, String> {
+                let mut parameters = ::ordermap::OrderMap::new();
+                #(#defaults)*
+                #(#values)*
+                parameters
+            }
+        }
+
+        impl crate::aliapis::sign::Api for #struct_identifier {
+            type Response = #response;
+
             fn new() -> Self {
-                let parameters = OrderMap::new();
-                #struct_identifier(parameters)
+                Self {
+                    #(#all_fields: ::core::default::Default::default(),)*
+                }
             }
 
             fn name(&self) -> String {
-                stringify!(#struct_identifier).to_string()
+                #action.to_string()
+            }
+
+            fn validate(&self) -> ::core::result::Result<(), crate::aliapis::error::ValidationError> {
+                let mut error = crate::aliapis::error::ValidationError::new(#action);
+                #(#required)*
+                #(self.#validators(&mut error);)*
+                error.into_result()
             }
 
-            fn send(self) -> impl std::future::Future<Output = Result<reqwest::Response, reqwest::Error>> {
-                RequestHeader::new(
-                    super::ENDPOINT.to_string(),
-                    self.name(),
-                    super::VERSION.to_string(),
-                    self.0,
-                )
-                .sign()
-                .send()
+            fn canonical_request(self) -> crate::aliapis::sign::RequestHeader {
+                let parameters = self.parameters();
+                let request = #request;
+                #(#region)*
+                request
+                #method
+                #idempotent
+                .set_signature_version(#signature_version)
+                #body
             }
         }
+    })
+}
+
+// 解析字段的 `#[api(...)]`，不是请求参数的字段返回 None
+fn parse_field(field: syn::Field) -> syn::Result<Option<Parameter>> {
+    let ident = field.ident.clone().expect("named field");
+    let mut name = None;
+    let mut skip = false;
+    let mut required = false;
+    let mut region = false;
+    let mut format = None;
+    let mut offset = None;
+    let mut setter = Some(syn::Ident::new(
+        &format!("set_{}", unraw(&ident)),
+        ident.span(),
+    ));
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("api")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.path.is_ident("skip") {
+                skip = true;
+            } else if meta.path.is_ident("required") {
+                required = true;
+            } else if meta.path.is_ident("region") {
+                region = true;
+            } else if meta.path.is_ident("format") {
+                format = Some(meta.value()?.parse::<syn::LitStr>()?);
+            } else if meta.path.is_ident("offset") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                offset = Some(
+                    parse_offset(&value.value())
+                        .ok_or_else(|| meta.error("offset must look like \"+08:00\""))?,
+                );
+            } else if meta.path.is_ident("setter") {
+                setter = match meta.value()?.parse::<syn::Lit>()? {
+                    syn::Lit::Bool(value) if !value.value => None,
+                    syn::Lit::Str(value) => Some(value.parse()?),
+                    _ => return Err(meta.error("setter must be false or a method name")),
+                };
+            } else {
+                return Err(meta.error("unsupported api field attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    if skip {
+        return Ok(None);
+    }
+    let name = name.ok_or_else(|| {
+        syn::Error::new(
+            ident.span(),
+            "parameter fields need #[api(name = \"...\")] or #[api(skip)]",
+        )
+    })?;
+    let ty = option_inner(&field.ty).ok_or_else(|| {
+        syn::Error::new(ident.span(), "parameter fields must be Option<T>")
+    })?;
+    if offset.is_some() && format.is_none() {
+        return Err(syn::Error::new(
+            ident.span(),
+            "offset can only be used together with format",
+        ));
+    }
+    let format = format.map(|format| (format, offset.unwrap_or(0)));
+    let docs = field
+        .attrs
+        .into_iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .collect();
+    Ok(Some(Parameter {
+        ident,
+        name,
+        ty,
+        setter,
+        required,
+        region,
+        format,
+        docs,
+    }))
+}
+
+// "+08:00"、"-05:30" 转换为秒数
+fn parse_offset(value: &str) -> Option<i32> {
+    let (sign, rest) = match value.as_bytes().first()? {
+        b'+' => (1, &value[1..]),
+        b'-' => (-1, &value[1..]),
+        _ => return None,
     };
-    expanded.into()
+    let (hours, minutes) = rest.split_once(':')?;
+    let (hours, minutes): (i32, i32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60))
 }
 
+fn option_inner(ty: &syn::Type) -> Option<syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(arguments) => match arguments.args.first()? {
+            syn::GenericArgument::Type(ty) => Some(ty.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
 
+fn is_number(ty: &syn::Type) -> bool {
+    const NUMBERS: [&str; 14] = [
+        "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+        "f32", "f64",
+    ];
+    matches!(ty, syn::Type::Path(path) if NUMBERS.iter().any(|number| path.path.is_ident(number)))
+}
+
+fn unraw(ident: &syn::Ident) -> String {
+    ident.to_string().trim_start_matches("r#").to_string()
+}
@@ -1,29 +1,29 @@
-use ordermap::OrderMap;
+use api_derive::Api;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::aliapis::sign::{Api, RequestHeader};
+use crate::aliapis::{
+    arms::SyntheticDetail,
+    error::ValidationError,
+    paginate::{next_number, page_count, Page, Paginated},
+    param::{check_range, SortDirection},
+};
 
-#[derive(Debug, Clone)]
-pub struct ListSyntheticDetail(OrderMap<String, String>);
-impl ListSyntheticDetail {
-    ///地域 ID，当前只支持 cn-hangzhou。
-    pub fn set_region_id(mut self, region_id: &str) -> Self {
-        self.0
-            .insert_sorted("RegionID".into(), region_id.to_string());
-        self
-    }
+#[derive(Debug, Clone, Api)]
+#[api(
+    default(Category = "SYNTHETIC", PageSize = 300),
+    validate_with = "check_parameters"
+)]
+pub struct ListSyntheticDetail {
+    ///地域 ID，未设置时使用客户端的地域。
+    #[api(name = "RegionId", region)]
+    region_id: Option<String>,
     ///拨测类型，必填，即时拨测：1，定时拨测：2。
-    pub fn set_synthetic_type(mut self, synthetic_type: &str) -> Self {
-        self.0
-            .insert_sorted("SyntheticType".into(), synthetic_type.to_string());
-        self
-    }
+    #[api(name = "SyntheticType", required)]
+    synthetic_type: Option<i64>,
     ///结果分类，必填，写死：SYNTHETIC。
-    pub fn set_category(mut self, category: &str) -> Self {
-        self.0
-            .insert_sorted("Category".into(), category.to_string());
-        self
-    }
+    #[api(name = "Category")]
+    category: Option<String>,
     ///拨测结果内容，必填，获取不同类型的拨测结果传不同的值：
     ///PING 列表：ICMP_LIST
     ///TCP 列表：TCP_LIST
@@ -32,98 +32,90 @@ impl ListSyntheticDetail {
     ///网站测速列表：WEBSITE_LIST
     ///文件下载列表：DOWNLOAD_LIST
     ///拨测结果明细：ALL
-    pub fn set_detail(mut self, detail: &str) -> Self {
-        self.0.insert_sorted("Detail".into(), detail.to_string());
-        self
+    #[api(name = "Detail", required)]
+    detail: Option<SyntheticDetail>,
+    ///查询起始时间，必填，以毫秒时间戳发送。
+    #[api(name = "StartTime", required, format = "millis")]
+    start_time: Option<DateTime<Utc>>,
+    ///查询结束时间，必填，以毫秒时间戳发送。
+    #[api(name = "EndTime", required, format = "millis")]
+    end_time: Option<DateTime<Utc>>,
+    ///排序字段，仅支持：timestamp（拨测发起时间）。
+    #[api(name = "OrderBy")]
+    order_by: Option<String>,
+    ///排序标准。取值：
+    ///ASC：升序。
+    ///DESC：降序。
+    #[api(name = "Order")]
+    order: Option<SortDirection>,
+    ///查询页码，从 1 开始。
+    #[api(name = "Page")]
+    page: Option<i64>,
+    ///分页大小。
+    #[api(name = "PageSize")]
+    page_size: Option<i64>,
+    #[api(name = "Filters", required, setter = false)]
+    filters: Option<String>,
+    #[api(name = "AdvancedFilters", setter = false)]
+    advanced_filters: Option<String>,
+}
+
+impl ListSyntheticDetail {
+    // 拨测类型只有 1 和 2，结束时间不能早于开始时间
+    fn check_parameters(&self, error: &mut ValidationError) {
+        check_range(error, "SyntheticType", self.synthetic_type, 1..=2);
+        if let (Some(start), Some(end)) = (self.start_time, self.end_time) {
+            if end < start {
+                error.invalid("EndTime", "must not be earlier than StartTime");
+            }
+        }
     }
+
     ///筛选条件，必填。
-    ///查任务拨测结果列表：{"taskId":"${taskId}"}
-    ///查任务拨测结果详情：{"taskId":"${taskId}","dataId":"${dataId}"}
+    ///查任务拨测结果列表：`{"taskId":"${taskId}"}`
+    ///查任务拨测结果详情：`{"taskId":"${taskId}","dataId":"${dataId}"}`
     pub fn set_filters(mut self, task_id: &str) -> Self {
-        //let start = r#"{{\"taskId\": \""#;
-        //let end = r#""}"#;
-        let filter = format!("{{\"taskId\": \"{}\"}}", task_id);
-        self.0.insert_sorted("Filters".into(), filter);
+        self.filters = Some(format!("{{\"taskId\": \"{}\"}}", task_id));
         self
     }
 
     ///筛选条件数组，必填。
-    ///查询拨测结果列表：[{"Key":"taskType","OpType":"in","Value":[任务类型]}]。
-    ///查询拨测结果详情：[{"Key":"dataId","OpType":"eq","Value":"dataId"}] ，dataId 为查任务拨测结果列表返回值的 dataId 字段。
+    ///查询拨测结果列表：`[{"Key":"taskType","OpType":"in","Value":[任务类型]}]`。
+    ///查询拨测结果详情：`[{"Key":"dataId","OpType":"eq","Value":"dataId"}]`，dataId 为查任务拨测结果列表返回值的 dataId 字段。
     pub fn set_advanced_filters(mut self, key: &str, op: &str, value: &str) -> Self {
-        let advanced_filters = format!(
+        self.advanced_filters = Some(format!(
             "[{{\"Key\":\"{}\",\"OpType\":\"{}\",\"Value\":\"{}\"}}]",
             key, op, value
-        );
-        self.0
-            .insert_sorted("".into(), advanced_filters.to_string());
-        self
-    }
-    ///查询起始时间的时间戳，必填，精确到毫秒。
-    pub fn set_start_time(mut self, start_time: &str) -> Self {
-        self.0
-            .insert_sorted("StartTime".into(), start_time.to_string());
-        self
-    }
-    ///查询结束时间的时间戳，必填，精确到毫秒。
-    pub fn set_end_time(mut self, end_time: &str) -> Self {
-        self.0.insert_sorted("EndTime".into(), end_time.to_string());
-        self
-    }
-    ///排序字段，仅支持：timestamp（拨测发起时间）。
-    pub fn set_order_by(mut self, order_by: &str) -> Self {
-        self.0.insert_sorted("OrderBy".into(), order_by.to_string());
-        self
-    }
-    ///排序标准。取值：
-    ///ASC：升序。
-    ///DESC：降序。
-    pub fn set_order(mut self, order: &str) -> Self {
-        self.0.insert_sorted("Order".into(), order.to_string());
-        self
-    }
-    ///查询页码，从 1 开始。
-    pub fn set_page(mut self, page: &str) -> Self {
-        self.0.insert_sorted("Page".into(), page.to_string());
-        self
-    }
-    ///分页大小。
-    pub fn set_page_size(mut self, page_size: &str) -> Self {
-        self.0
-            .insert_sorted("PageSize".into(), page_size.to_string());
+        ));
         self
     }
 }
 
-impl Api for ListSyntheticDetail {
-    // 使用默认参数实例化
-    fn new() -> Self {
-        let mut parameters = OrderMap::new();
-        parameters.insert_sorted("PageSize".to_string(), "300".to_string());
-        parameters.insert_sorted("RegionId".to_string(), "cn-hangzhou".to_string());
-        parameters.insert_sorted("Category".to_string(), "SYNTHETIC".to_string());
+impl Paginated for ListSyntheticDetail {
+    type Item = Item;
 
-        ListSyntheticDetail(parameters)
+    fn with_page(self, page: &Page) -> Self {
+        match page {
+            Page::Number(number) => self.set_page(*number as i64),
+            Page::Token(_) => self,
+        }
     }
-
-    fn name(&self) -> String {
-        "ListSyntheticDetail".into()
+    fn next_page(&self, page: &Page, response: &Self::Response) -> Option<Page> {
+        next_number(page, self.total_pages(response)?)
     }
-
-    fn send(self) -> impl std::future::Future<Output = Result<reqwest::Response, reqwest::Error>> {
-        RequestHeader::new(
-            super::ENDPOINT.to_string(),
-            self.name(),
-            super::VERSION.to_string(),
-            self.0,
-        )
-        .sign()
-        .send()
+    fn total_pages(&self, response: &Self::Response) -> Option<u64> {
+        Some(page_count(
+            response.data.total as u64,
+            response.data.page_size as u64,
+        ))
+    }
+    fn into_items(response: Self::Response) -> Vec<Self::Item> {
+        response.data.items
     }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct ListSyntheticDetailResponse {
     pub request_id: String,
     pub data: Data,
@@ -131,7 +123,7 @@ pub struct ListSyntheticDetailResponse {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Data {
     pub page_size: i64,
     pub total: i64,
@@ -140,7 +132,7 @@ pub struct Data {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Item {
     #[serde(rename = "__time__")]
     pub time: i64,
@@ -184,30 +176,74 @@ pub struct Item {
     pub ip_region: String,
     #[serde(rename = "taskName")]
     pub task_name: String,
-    #[serde(rename = "timesta
-    fn canonical_request(self) -> RequestHeader;mp")]
+    #[serde(rename = "timestamp")]
     pub timestamp: String,
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::aliapis::sign::api_tests;
 
-    use chrono::*;
+    use crate::aliapis::{
+        arms::{list_synthetic_detail::ListSyntheticDetail, SyntheticDetail},
+        sign::Api,
+    };
+    use chrono::{DateTime, TimeZone, Utc};
 
-    use crate::aliapis::{arms::list_synthetic_detail::ListSyntheticDetail, sign::Api};
+    fn millis(timestamp: i64) -> DateTime<Utc> {
+        Utc.timestamp_millis_opt(timestamp).unwrap()
+    }
 
-    #[tokio::test]
-    async fn works() -> anyhow::Result<()> {
-        let test_api = ListSyntheticDetail::new()
-            .set_end_time(&Local::now().timestamp_millis().to_string())
-            .set_start_time(&(Local::now().timestamp_millis() - 24 * 60 * 60 * 1000).to_string())
-            .set_synthetic_type("2")
-            .set_detail("DOWNLOAD_LIST")
-            .set_page("1")
-            .set_filters("f124d885bcbc4d78a7fcb3a020b6ad66");
-        let response = test_api.send().await?;
+    #[test]
+    fn region_id() {
+        let parameters = ListSyntheticDetail::new()
+            .set_region_id("cn-shanghai")
+            .parameters();
+        assert_eq!(parameters["RegionId"], "cn-shanghai");
+        assert!(!parameters.contains_key("RegionID"));
+    }
 
-        assert_eq!(response.status(), 200);
-        Ok(())
+    api_tests! {
+        snapshot: ListSyntheticDetail::new()
+            .set_end_time(millis(1704067200000))
+            .set_start_time(millis(1703980800000))
+            .set_synthetic_type(2)
+            .set_detail(SyntheticDetail::DownloadList)
+            .set_page(1)
+            .set_filters("f124d885bcbc4d78a7fcb3a020b6ad66"),
+        canonical_request: "GET\n\
+             /\n\
+             Category=SYNTHETIC&Detail=DOWNLOAD_LIST&EndTime=1704067200000&Filters=%7B%22taskId%22%3A%20%22f124d885bcbc4d78a7fcb3a020b6ad66%22%7D&Page=1&PageSize=300&RegionId=cn-hangzhou&StartTime=1703980800000&SyntheticType=2\n\
+             host:arms.cn-hangzhou.aliyuncs.com\n\
+             x-acs-action:ListSyntheticDetail\n\
+             x-acs-content-sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\n\
+             x-acs-date:2024-01-01T00:00:00Z\n\
+             x-acs-signature-nonce:3156853299f313e23d1673dc12e1703d\n\
+             x-acs-version:2019-08-08\n\
+             \n\
+             host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version\n\
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        signature: "57530c29dd61d8ffafc8251669d33113af33b05b16b841b1fb056d6972a00dc2",
+        cassette: "arms/list_synthetic_detail" => ListSyntheticDetail::new()
+            .set_end_time(millis(1704067200000))
+            .set_start_time(millis(1703980800000))
+            .set_synthetic_type(2)
+            .set_detail(SyntheticDetail::DownloadList)
+            .set_page(1)
+            .set_filters("f124d885bcbc4d78a7fcb3a020b6ad66"),
+        response: |response| {
+            assert_eq!(response.code, 200);
+            assert_eq!(response.request_id, "0F5A7C7E-3B1D-5E8A-9C21-6D4E8B2F1A30");
+            assert_eq!((response.data.page, response.data.total), (1, 2));
+            let [ok, timeout] = &response.data.items[..] else {
+                panic!("unexpected items {:?}", response.data.items);
+            };
+            assert_eq!(ok.time, 1704003660);
+            assert_eq!(ok.ip_city, "杭州");
+            assert_eq!(ok.download_speed, "9536.7");
+            assert_eq!(ok.timestamp, "1704003660000");
+            assert_eq!(timeout.response_code, "504");
+            assert_eq!(timeout.err_name, "下载超时");
+        },
     }
 }
@@ -3,4 +3,7 @@ pub use list_synthetic_detail::{ListSyntheticDetail,ListSyntheticDetailResponse}
 
 
 const VERSION: &str = "2019-08-08";
-const ENDPOINT: &str = "arms.cn-hangzhou.aliyuncs.com";
\ No newline at end of file
+const PRODUCT: &str = "ARMS";
+
+mod types;
+pub use types::*;
@@ -0,0 +1,21 @@
+use crate::aliapis::param::param_enum;
+
+param_enum! {
+    /// 拨测结果内容
+    pub enum SyntheticDetail {
+        ///PING 列表
+        IcmpList = "ICMP_LIST",
+        ///TCP 列表
+        TcpList = "TCP_LIST",
+        ///DNS 列表
+        DnsList = "DNS_LIST",
+        ///HTTP(s) 列表
+        HttpList = "HTTP_LIST",
+        ///网站测速列表
+        WebsiteList = "WEBSITE_LIST",
+        ///文件下载列表
+        DownloadList = "DOWNLOAD_LIST",
+        ///拨测结果明细
+        All = "ALL",
+    }
+}
@@ -1,127 +1,112 @@
 use api_derive::Api;
-use ordermap::OrderMap;
+use chrono::NaiveDate;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::aliapis::{
+    billing::{Granularity, SubscriptionType},
+    error::ValidationError,
+    paginate::{Page, Paginated},
+    param::{check_range, YearMonth},
+};
+
 ///查询用户某个账期内所有商品实例或计费项的消费汇总
 #[derive(Debug, Clone, Api)]
-pub struct DescribeInstanceBill(OrderMap<String, String>);
-
-
-impl DescribeInstanceBill {
-    /// 必选，账期 YYYY－MM。仅支持最近 18 个月账期。实例：2024-11
-    pub fn set_billing_cycle(mut self, billing_cycle: &str) -> Self {
-        self.0
-            .insert_sorted("BillingCycle".into(), billing_cycle.into());
-        self
-    }
+#[api(validate_with = "check_parameters")]
+pub struct DescribeInstanceBill {
+    /// 必选，账期，按北京时间划分。仅支持最近 18 个月账期。
+    #[api(name = "BillingCycle", required)]
+    billing_cycle: Option<YearMonth>,
     /// 产品代码
-    pub fn set_product_code(mut self, product_code: &str) -> Self {
-        self.0
-            .insert_sorted("ProductCode".into(), product_code.into());
-        self
-    }
+    #[api(name = "ProductCode")]
+    product_code: Option<String>,
     /// 产品类型
-    pub fn set_product_type(mut self, product_type: &str) -> Self {
-        self.0
-            .insert_sorted("ProductType".into(), product_type.into());
-        self
-    }
+    #[api(name = "ProductType")]
+    product_type: Option<String>,
     ///订阅类型。取值：
     ///Subscription：预付费。
     ///PayAsYouGo：后付费。
-    pub fn set_subscription_type(mut self, subscription_type: &str) -> Self {
-        self.0
-            .insert_sorted("SubscriptionType".into(), subscription_type.into());
-        self
-    }
+    #[api(name = "SubscriptionType")]
+    subscription_type: Option<SubscriptionType>,
     ///是否按照计费项维度拉取数据。
     ///false。与费用中心-费用账单-账单明细-实例账单一致。
     ///true。与费用中心-费用账单-账单明细-计费项账单一致。
-    pub fn set_is_billing_item(mut self, is_billing_item: &str) -> Self {
-        self.0
-            .insert_sorted("IsBillingItem".into(), is_billing_item.into());
-        self
-    }
+    #[api(name = "IsBillingItem")]
+    is_billing_item: Option<bool>,
     ///根据原价（PretaxGrossAmount）和应付（PretaxAmount）是否都为 0 做过滤。取值：
     ///false。
     ///true。
-    pub fn set_is_hide_zero_charge(mut self, is_hide_zero_charge: &str) -> Self {
-        self.0
-            .insert_sorted("IsHideZeroCharge".into(), is_hide_zero_charge.into());
-        self
-    }
-    ///账单日期，仅当 Granularity 为 DAILY 时必填，格式为 YYYY-MM-DD。
-    pub fn set_billing_date(mut self, billing_date: &str) -> Self {
-        self.0
-            .insert_sorted("BillingDate".into(), billing_date.into());
-        self
-    }
+    #[api(name = "IsHideZeroCharge")]
+    is_hide_zero_charge: Option<bool>,
+    ///账单日期（北京时间），仅当 Granularity 为 DAILY 时必填。
+    #[api(name = "BillingDate")]
+    billing_date: Option<NaiveDate>,
     ///查询账单的颗粒度。取值如下：
     ///MONTHLY：月。与费用中心-费用账单-账单明细-账期账单一致。
     ///DAILY：日。与费用中心-费用账单-账单明细-按天账单一致。
-    pub fn set_granularity(mut self, granularity: &str) -> Self {
-        self.0
-            .insert_sorted("Granularity".into(), granularity.into());
-        self
-    }
+    #[api(name = "Granularity")]
+    granularity: Option<Granularity>,
     ///资源归属账号 ID，资源归属账号是实际使用资源的账号。
-    pub fn set_bill_owner_id(mut self, bill_owner_id: &str) -> Self {
-        self.0
-            .insert_sorted("BillOwnerId".into(), bill_owner_id.into());
-        self
-    }
+    #[api(name = "BillOwnerId")]
+    bill_owner_id: Option<String>,
     ///实例 ID。
-    pub fn set_instance_id(mut self, instance_id: &str) -> Self {
-        self.0
-            .insert_sorted("InstanceID".into(), instance_id.into());
-        self
-    }
+    #[api(name = "InstanceID")]
+    instance_id: Option<String>,
     ///产品 Code，与费用中心账单产品 Code 一致。
-    pub fn set_pip_code(mut self, pip_code: &str) -> Self {
-        self.0.insert_sorted("PipCode".into(), pip_code.into());
-        self
-    }
+    #[api(name = "PipCode")]
+    pip_code: Option<String>,
     ///用来表示当前调用开始读取的位置，参数值必须为空或者使用返回结果中的 NextToken 设值，否则会报错。空代表从头开始读取。
-    pub fn set_next_token(mut self, next_token: &str) -> Self {
-        self.0.insert_sorted("NextToken".into(), next_token.into());
-        self
-    }
+    #[api(name = "NextToken")]
+    next_token: Option<String>,
     ///本次读取的最大数据记录数量。默认值：300，最大值：300。
-    pub fn set_max_resutls(mut self, max_resutls: &str) -> Self {
-        self.0
-            .insert_sorted("MaxResults".into(), max_resutls.into());
-        self
-    }
+    #[api(name = "MaxResults")]
+    max_results: Option<i64>,
 }
-/* 
-impl Api for DescribeInstanceBill {
-    fn new() -> Self {
-        let mut parameters: OrderMap<String, String> = OrderMap::new();
-        parameters.insert_sorted("MaxResults".into(), "300".into());
 
-        DescribeInstanceBill(parameters)
+impl DescribeInstanceBill {
+    // 按天查询时需要账单日期，且账单日期在账期内
+    fn check_parameters(&self, error: &mut ValidationError) {
+        match (self.billing_cycle, self.billing_date) {
+            (_, None) if self.granularity == Some(Granularity::Daily) => {
+                error.missing("BillingDate")
+            }
+            (Some(cycle), Some(date)) if YearMonth::from(date) != cycle => {
+                error.invalid("BillingDate", format!("must be in BillingCycle {}", cycle))
+            }
+            _ => {}
+        }
+        check_range(error, "MaxResults", self.max_results, 1..=300);
     }
 
-    fn name(&self) -> String {
-        "DescribeInstanceBill".to_string()
+    #[deprecated(note = "use `set_max_results`")]
+    pub fn set_max_resutls(self, max_results: i64) -> Self {
+        self.set_max_results(max_results)
     }
+}
+
+impl Paginated for DescribeInstanceBill {
+    type Item = Item;
 
-    fn send(self) -> impl std::future::Future<Output = Result<reqwest::Response, reqwest::Error>> {
-        RequestHeader::new(
-            super::ENDPOINT.to_string(),
-            self.name(),
-            super::VERSION.to_string(),
-            self.0,
-        )
-        .sign()
-        .send()
+    fn first_page() -> Page {
+        Page::Token(String::new())
+    }
+    fn with_page(self, page: &Page) -> Self {
+        match page {
+            Page::Token(token) if !token.is_empty() => self.set_next_token(token),
+            _ => self,
+        }
+    }
+    fn next_page(&self, _page: &Page, response: &Self::Response) -> Option<Page> {
+        let token = &response.data.next_token;
+        (!token.is_empty()).then(|| Page::Token(token.clone()))
+    }
+    fn into_items(response: Self::Response) -> Vec<Self::Item> {
+        response.data.items
     }
 }
-*/
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct DescribeInstanceBillResponse {
     pub message: String,
     pub request_id: String,
@@ -131,7 +116,7 @@ pub struct DescribeInstanceBillResponse {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Data {
     pub billing_cycle: String,
     pub total_count: f64,
@@ -140,10 +125,13 @@ pub struct Data {
     pub max_results: f64,
     pub items: Vec<Item>,
     pub account_name: String,
+    /// 下一页的 NextToken，最后一页为空
+    #[serde(default)]
+    pub next_token: String,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Item {
     pub instance_spec: String,
     pub product_name: String,
@@ -199,15 +187,78 @@ pub struct Item {
 
 #[cfg(test)]
 mod tests {
+    use crate::aliapis::sign::api_tests;
     use crate::{aliapis::billing::describe_instance_bill::DescribeInstanceBill, Api};
+    use crate::billing::Granularity;
+    use crate::{ParamProblem, ValidationError, YearMonth};
+
+    fn january() -> YearMonth {
+        YearMonth::new(2024, 1).unwrap()
+    }
 
-    #[tokio::test]
-    async fn works() -> anyhow::Result<()> {
-        let billing_cycle = chrono::Local::now().format("%Y-%m").to_string();
-        let test_api = DescribeInstanceBill::new().set_billing_cycle(&billing_cycle);
-        let response = test_api.send().await?;
+    #[test]
+    fn validate() {
+        let api = DescribeInstanceBill::new().set_billing_cycle(january());
+        assert_eq!(api.validate(), Ok(()));
+        assert_eq!(
+            DescribeInstanceBill::new().validate(),
+            Err(ValidationError {
+                action: "DescribeInstanceBill".to_string(),
+                problems: vec![ParamProblem::Missing("BillingCycle".to_string())],
+            })
+        );
+
+        let error = DescribeInstanceBill::new()
+            .set_billing_cycle(january())
+            .set_granularity(Granularity::Daily)
+            .set_max_results(500)
+            .validate()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "DescribeInstanceBill: missing required parameter BillingDate; \
+             invalid parameter MaxResults: must be between 1 and 300, got 500"
+        );
+
+        let api = DescribeInstanceBill::new()
+            .set_billing_cycle(january())
+            .set_granularity(Granularity::Daily)
+            .set_billing_date(january().previous().unwrap().first_day());
+        assert_eq!(
+            api.validate().unwrap_err().to_string(),
+            "DescribeInstanceBill: invalid parameter BillingDate: must be in BillingCycle 2024-01"
+        );
+        let api = api.set_billing_date(january().first_day());
+        assert_eq!(api.parameters()["BillingDate"], "2024-01-01");
+    }
 
-        assert_eq!(response.status(), 200);
-        Ok(())
+    api_tests! {
+        snapshot: DescribeInstanceBill::new().set_billing_cycle(january()),
+        canonical_request: "GET\n\
+             /\n\
+             BillingCycle=2024-01\n\
+             host:business.aliyuncs.com\n\
+             x-acs-action:DescribeInstanceBill\n\
+             x-acs-content-sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\n\
+             x-acs-date:2024-01-01T00:00:00Z\n\
+             x-acs-signature-nonce:3156853299f313e23d1673dc12e1703d\n\
+             x-acs-version:2017-12-14\n\
+             \n\
+             host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version\n\
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        signature: "52b8a0ad31ceb36e32ae4eb3195bc40ee02b4da3a5adfb15ddf7da32e526d8fc",
+        cassette: "billing/describe_instance_bill" => DescribeInstanceBill::new().set_billing_cycle(january()),
+        response: |response| {
+            assert!(response.success);
+            assert_eq!(response.request_id, "5B2E9D41-7C6A-5F03-A8D4-2E1B9C7F6A58");
+            assert_eq!(response.data.billing_cycle, "2024-01");
+            assert_eq!(response.data.account_id, "1234567890123456");
+            assert_eq!(response.data.total_count, 1.0);
+            let item = &response.data.items[0];
+            assert_eq!(item.instance_id, "i-bp1example0000000001");
+            assert_eq!(item.intranet_ip, "172.16.0.12");
+            assert_eq!(item.subscription_type, "Subscription");
+            assert_eq!(item.payment_amount, 216.0);
+        },
     }
 }
@@ -1,22 +1,15 @@
 use api_derive::Api;
 
-use ordermap::OrderMap;
 use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Clone, Api)]
-pub struct GetOrderDetail(OrderMap<String, String>);
-
-impl GetOrderDetail {
-    pub fn set_order_id(mut self, order_id: &str) -> Self {
-        self.0
-            .insert_sorted("OrderId".into(), order_id.into());
-        self
-    }
+pub struct GetOrderDetail {
+    #[api(name = "OrderId", required)]
+    order_id: Option<String>,
 }
 
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
 pub struct GetOrderDetailResponse {
     pub message: String,
     pub request_id: String,
@@ -25,20 +18,20 @@ pub struct GetOrderDetailResponse {
     pub success: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Data {
     pub order_list: OrderList,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
 pub struct OrderList {
     pub order: Vec<Order>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Order {
     pub operator: Option<String>,
     pub product_code: String,
@@ -65,40 +58,40 @@ pub struct Order {
     pub extend_infos: ExtendInfos,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct BillModuleConfig {
     pub bill_module_config: Vec<Option<serde_json::Value>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
 pub struct ExtendInfos {
     pub discount_amount: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct OriginalModuleConfig {
     pub original_module_config: Vec<OriginalModuleConfigElement>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
 pub struct OriginalModuleConfigElement {
     pub module_properties: ModuleProperties,
     pub code: String,
     pub name: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct ModuleProperties {
     pub module_properties: Vec<ModuleProperty>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
 pub struct ModuleProperty {
     pub value: String,
     pub name: String,
@@ -106,16 +99,37 @@ pub struct ModuleProperty {
 
 #[cfg(test)]
 mod tests {
+    use crate::aliapis::sign::api_tests;
     use crate::Api;
 
-
-    #[tokio::test]
-    async fn works() -> anyhow::Result<()> {
-        let billing_cycle = chrono::Local::now().format("%Y-%m").to_string();
-        let test_api = super::GetOrderDetail::new().set_order_id("2024010100000001");
-        let response = test_api.send().await?;
-
-        assert_eq!(response.status(), 200);
-        Ok(())
+    api_tests! {
+        snapshot: super::GetOrderDetail::new().set_order_id("2024010100000001"),
+        canonical_request: "GET\n\
+             /\n\
+             OrderId=2024010100000001\n\
+             host:business.aliyuncs.com\n\
+             x-acs-action:GetOrderDetail\n\
+             x-acs-content-sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\n\
+             x-acs-date:2024-01-01T00:00:00Z\n\
+             x-acs-signature-nonce:3156853299f313e23d1673dc12e1703d\n\
+             x-acs-version:2017-12-14\n\
+             \n\
+             host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version\n\
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        signature: "3087d6e1f85d1933da4adfeb462648abb5192aa5449d955f64cf5974b27bc8d7",
+        cassette: "billing/get_order_detail" => super::GetOrderDetail::new().set_order_id("2024010100000001"),
+        response: |response| {
+            assert!(response.success);
+            assert_eq!(response.request_id, "C7D14E2A-91B8-5A6F-B3E0-4F8A2D6C1E97");
+            let order = &response.data.order_list.order[0];
+            assert_eq!(order.order_id, "2024010100000001");
+            assert_eq!(order.payment_time.as_deref(), Some("2024-01-01T10:21:05Z"));
+            assert_eq!(order.instance_i_ds, "i-bp1example0000000001");
+            assert_eq!(order.pretax_amount, 216.0);
+            let module = &order.original_module_config.original_module_config[0];
+            assert_eq!(module.code, "InstanceType");
+            assert_eq!(module.module_properties.module_properties[0].value, "ecs.c7.large");
+            assert_eq!(order.extend_infos.discount_amount, "0.0");
+        },
     }
 } 
\ No newline at end of file
@@ -1,5 +1,5 @@
 const VERSION: &str = "2017-12-14";
-const ENDPOINT: &str = "business.aliyuncs.com";
+const PRODUCT: &str = "BssOpenApi";
 
 pub mod describe_instance_bill;
 pub use describe_instance_bill::{DescribeInstanceBill,DescribeInstanceBillResponse};
@@ -18,3 +18,6 @@ pub use query_orders::{QueryOrders,QueryOrdersResponse};
 
 pub mod get_order_detail;
 pub use get_order_detail::{GetOrderDetail,GetOrderDetailResponse};
+
+mod types;
+pub use types::*;
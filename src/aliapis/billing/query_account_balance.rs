@@ -1,39 +1,14 @@
-use ordermap::OrderMap;
 use serde::Deserialize;
 use serde::Serialize;
 use api_derive::Api;
 
 ///查询用户账户余额信息
 #[derive(Debug, Clone, Api)]
-pub struct QueryAccountBalance(OrderMap<String, String>);
-
-/* 
-impl Api for QueryAccountBalance {
-    fn new() -> Self {
-        let parameters = OrderMap::new();
-        QueryAccountBalance(parameters)
-    }
-
-    fn name(&self) -> String {
-        "QueryAccountBalance".to_string()
-    }
-
-    fn send(self) -> impl std::future::Future<Output = Result<reqwest::Response, reqwest::Error>> {
-        RequestHeader::new(
-            super::ENDPOINT.to_string(),
-            self.name(),
-            super::VERSION.to_string(),
-            self.0,
-        )
-        .sign()
-        .send()
-    }
-}
-*/
+pub struct QueryAccountBalance {}
 
 // Response Struct
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct QueryAccountBalanceResponse {
     pub message: String,
     pub request_id: String,
@@ -43,7 +18,7 @@ pub struct QueryAccountBalanceResponse {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Data {
     pub available_cash_amount: String,
     pub mybank_credit_amount: String,
@@ -55,14 +30,31 @@ pub struct Data {
 
 #[cfg(test)]
 mod tests {
+    use crate::aliapis::sign::api_tests;
     use crate::{aliapis::billing::query_account_balance::QueryAccountBalance, Api};
 
-    #[tokio::test]
-    async fn works() -> anyhow::Result<()> {
-        let test_api = QueryAccountBalance::new();
-        let response = test_api.send().await?;
-
-        assert_eq!(response.status(), 200);
-        Ok(())
+    api_tests! {
+        snapshot: QueryAccountBalance::new(),
+        canonical_request: "GET\n\
+             /\n\
+             \n\
+             host:business.aliyuncs.com\n\
+             x-acs-action:QueryAccountBalance\n\
+             x-acs-content-sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\n\
+             x-acs-date:2024-01-01T00:00:00Z\n\
+             x-acs-signature-nonce:3156853299f313e23d1673dc12e1703d\n\
+             x-acs-version:2017-12-14\n\
+             \n\
+             host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version\n\
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        signature: "f5c588e3e395210c232da3988b6cc86a3e943b01a8d5a514ea0e954d542894d4",
+        cassette: "billing/query_account_balance" => QueryAccountBalance::new(),
+        response: |response| {
+            assert!(response.success);
+            assert_eq!(response.code, "Success");
+            assert_eq!(response.request_id, "3E8F6A1B-2D4C-5B79-8E0A-1C9D7F3B5A24");
+            assert_eq!(response.data.available_amount, "1,523.80");
+            assert_eq!(response.data.currency, "CNY");
+        },
     }
 }
@@ -1,98 +1,64 @@
-use crate::aliapis::sign::Api;
-use crate::aliapis::sign::RequestHeader;
-use ordermap::OrderMap;
+use api_derive::Api;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use serde::Serialize;
 
-#[derive(Debug, Clone)]
-pub struct QueryDPUtilizationDetail(OrderMap<String, String>);
+use crate::aliapis::{
+    error::ValidationError,
+    param::check_range,
+};
 
-impl QueryDPUtilizationDetail {
+#[derive(Debug, Clone, Api)]
+#[api(validate_with = "check_parameters")]
+pub struct QueryDPUtilizationDetail {
     ///筛选查询的实例 ID，为空时返回所有的使用的实例明细。
-    pub fn set_instance_id(mut self, instance_id: &str) -> Self {
-        self.0
-            .insert_sorted("InstanceId".into(), instance_id.into());
-        self
-    }
+    #[api(name = "InstanceId")]
+    instance_id: Option<String>,
     ///实例对应的规格。
-    pub fn set_instance_spec(mut self, instance_spec: &str) -> Self {
-        self.0
-            .insert_sorted("InstanceSpec".into(), instance_spec.into());
-        self
-    }
+    #[api(name = "InstanceSpec")]
+    instance_spec: Option<String>,
     ///商品 code，如 ecsRi、scu_bag 等。如果填写该字段，则 prodCode 字段不生效
-    pub fn set_commodity_code(mut self, commodity_code: &str) -> Self {
-        self.0
-            .insert_sorted("CommodityCode".into(), commodity_code.into());
-        self
-    }
+    #[api(name = "CommodityCode")]
+    commodity_code: Option<String>,
     ///被抵扣实例的 ID，为空时返回所有实例的明细数据。
-    pub fn set_deducted_instance_id(mut self, deducted_instance_id: &str) -> Self {
-        self.0
-            .insert_sorted("DeductedInstanceId".into(), deducted_instance_id.into());
-        self
-    }
-    ///开始时间，格式：YYYY-MM-dd HH:mm:ss。
-    pub fn set_start_time(mut self, start_time: &str) -> Self {
-        self.0.insert_sorted("StartTime".into(), start_time.into());
-        self
-    }
-
-    ///结束时间，格式：YYYY-MM-dd HH:mm:ss。
-    pub fn set_end_time(mut self, end_time: &str) -> Self {
-        self.0.insert_sorted("EndTime".into(), end_time.into());
-        self
-    }
+    #[api(name = "DeductedInstanceId")]
+    deducted_instance_id: Option<String>,
+    ///开始时间，按北京时间发送。
+    #[api(name = "StartTime", required, format = "%Y-%m-%d %H:%M:%S", offset = "+08:00")]
+    start_time: Option<DateTime<Utc>>,
+    ///结束时间，按北京时间发送。
+    #[api(name = "EndTime", required, format = "%Y-%m-%d %H:%M:%S", offset = "+08:00")]
+    end_time: Option<DateTime<Utc>>,
     ///查询从 LastToken 之后开始返回。第一次查询填 null，之后的从结果 NextToken 获取
-    pub fn set_last_token(mut self, last_token: &str) -> Self {
-        self.0.insert_sorted("LastToken".into(), last_token.into());
-        self
-    }
+    #[api(name = "LastToken")]
+    last_token: Option<String>,
     ///每次查询条数，默认为 20，最大值为 300。
-    pub fn set_limit(mut self, limit: &str) -> Self {
-        self.0.insert_sorted("Limit".into(), limit.into());
-        self
-    }
+    #[api(name = "Limit")]
+    limit: Option<i64>,
     ///是否包含财务云子账号的资源包用量
     ///true：包含
     ///false：不包含
-    pub fn set_include_share(mut self, include_share: &str) -> Self {
-        self.0
-            .insert_sorted("IncludeShare".into(), include_share.into());
-        self
-    }
+    #[api(name = "IncludeShare")]
+    include_share: Option<bool>,
     ///产品 code，如：ecs
-    pub fn set_prod_code(mut self, prod_code: &str) -> Self {
-        self.0.insert_sorted("ProdCode".into(), prod_code.into());
-        self
-    }
+    #[api(name = "ProdCode")]
+    prod_code: Option<String>,
 }
 
-impl Api for QueryDPUtilizationDetail {
-    fn new() -> Self {
-        let parameters = OrderMap::new();
-
-        QueryDPUtilizationDetail(parameters)
-    }
-
-    fn name(&self) -> String {
-        "QueryDPUtilizationDetail".to_string()
-    }
-
-    fn send(self) -> impl std::future::Future<Output = Result<reqwest::Response, reqwest::Error>> {
-        RequestHeader::new(
-            super::ENDPOINT.to_string(),
-            self.name(),
-            super::VERSION.to_string(),
-            self.0,
-        )
-        .sign()
-        .send()
+impl QueryDPUtilizationDetail {
+    // 结束时间不能早于开始时间，每次最多 300 条
+    fn check_parameters(&self, error: &mut ValidationError) {
+        if let (Some(start), Some(end)) = (self.start_time, self.end_time) {
+            if end < start {
+                error.invalid("EndTime", "must not be earlier than StartTime");
+            }
+        }
+        check_range(error, "Limit", self.limit, 1..=300);
     }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct QueryDPUtilizationDetailResponse {
     pub message: String,
     pub request_id: String,
@@ -102,20 +68,20 @@ pub struct QueryDPUtilizationDetailResponse {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Data {
     pub next_token: String,
     pub detail_list: DetailList,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct DetailList {
     pub detail_list: Vec<DetailList2>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct DetailList2 {
     pub res_code: String,
     pub uid: i64,
@@ -133,27 +99,54 @@ pub struct DetailList2 {
 
 #[cfg(test)]
 mod tests {
-    use chrono::Datelike;
+    use crate::aliapis::sign::api_tests;
+    use chrono::{DateTime, TimeZone, Utc};
 
     use crate::aliapis::{
-        billing::query_dp_utilization_detail::QueryDPUtilizationDetail, sign::Api,
+        billing::query_dp_utilization_detail::QueryDPUtilizationDetail,
+        param::china_standard_time,
+        sign::Api,
     };
 
-    #[tokio::test]
-    async fn works() -> anyhow::Result<()> {
-        let now = chrono::Local::now();
-        let current_year = now.year();
-        let current_month = now.month();
-        let previous_month = now.month0();
-        let start_time = format!("{}-{}-1 00:00:00", current_year, previous_month);
-        let end_time = format!("{}-{}-0 00:00:00", current_year, current_month);
-        let test_api = QueryDPUtilizationDetail::new()
-            .set_include_share("true")
-            .set_start_time(&start_time)
-            .set_end_time(&end_time);
-        let response = test_api.send().await?;
+    // 北京时间当月第一天零点
+    fn month_start(month: u32) -> DateTime<Utc> {
+        china_standard_time()
+            .with_ymd_and_hms(2024, month, 1, 0, 0, 0)
+            .unwrap()
+            .to_utc()
+    }
 
-        assert_eq!(response.status(), 200);
-        Ok(())
+    api_tests! {
+        snapshot: QueryDPUtilizationDetail::new()
+            .set_include_share(true)
+            .set_start_time(month_start(1))
+            .set_end_time(month_start(2)),
+        canonical_request: "GET\n\
+             /\n\
+             EndTime=2024-02-01%2000%3A00%3A00&IncludeShare=true&StartTime=2024-01-01%2000%3A00%3A00\n\
+             host:business.aliyuncs.com\n\
+             x-acs-action:QueryDPUtilizationDetail\n\
+             x-acs-content-sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\n\
+             x-acs-date:2024-01-01T00:00:00Z\n\
+             x-acs-signature-nonce:3156853299f313e23d1673dc12e1703d\n\
+             x-acs-version:2017-12-14\n\
+             \n\
+             host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version\n\
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        signature: "7cddd5fbd261bb4b26ea0394b6a8af205becd7548553ba84a23a8e62536f6b1d",
+        cassette: "billing/query_dp_utilization_detail" => QueryDPUtilizationDetail::new()
+            .set_include_share(true)
+            .set_start_time(month_start(1))
+            .set_end_time(month_start(2)),
+        response: |response| {
+            assert!(response.success);
+            assert_eq!(response.request_id, "A41C9E73-6F2B-5D08-9A5E-7B3D1F8C2E69");
+            assert!(response.data.next_token.is_empty());
+            let detail = &response.data.detail_list.detail_list[0];
+            assert_eq!(detail.instance_id, "ecsrisp-cn-example0001");
+            assert_eq!(detail.uid, 1234567890123456);
+            assert_eq!(detail.deducted_instance_id, "i-bp1example0000000001");
+            assert_eq!(detail.deduct_quantity, 1.0);
+        },
     }
 }
@@ -1,63 +1,62 @@
 use api_derive::Api;
+use chrono::{DateTime, Utc};
 
-use ordermap::OrderMap;
 use serde::{Serialize, Deserialize};
 
+use crate::aliapis::{
+    billing::SubscriptionType,
+    paginate::{next_number, page_count, Page, Paginated},
+};
 
 #[derive(Debug, Clone, Api)]
-pub struct QueryOrders(OrderMap<String, String>);
+pub struct QueryOrders {
+    ///订单创建的结束时间，按 ISO8601 格式的 UTC 时间发送。
+    #[api(name = "CreateTimeEnd")]
+    create_time_end: Option<DateTime<Utc>>,
+    #[api(name = "ProdCode")]
+    prod_code: Option<String>,
+    ///订单创建的起始时间，按 ISO8601 格式的 UTC 时间发送。
+    #[api(name = "CreateTimeStart")]
+    create_time_start: Option<DateTime<Utc>>,
+    #[api(name = "PageNum")]
+    page_num: Option<i64>,
+    #[api(name = "PageSize")]
+    page_size: Option<i64>,
+    #[api(name = "ProductType")]
+    product_type: Option<String>,
+    #[api(name = "SubscriptionType")]
+    subscription_type: Option<SubscriptionType>,
+    #[api(name = "PaymentStatus")]
+    payment_status: Option<String>,
+    #[api(name = "OrderType")]
+    order_type: Option<String>,
+}
 
-impl QueryOrders {
-    pub fn set_create_time_end(mut self, create_time_end: &str) -> Self {
-        self.0
-            .insert_sorted("CreateTimeEnd".into(), create_time_end.into());
-        self
-    }
-    pub fn set_prod_code(mut self, prod_code: &str) -> Self {
-        self.0
-            .insert_sorted("ProdCode".into(), prod_code.into());
-        self
-    }
-    pub fn set_create_time_start(mut self, create_time_start: &str) -> Self {
-        self.0
-            .insert_sorted("CreateTimeStart".into(), create_time_start.into());
-        self
-    }
-    pub fn set_page_num(mut self, page_num: &str) -> Self {
-        self.0
-            .insert_sorted("PageNum".into(), page_num.into());
-        self
-    }
-    pub fn set_page_size(mut self, page_size: &str) -> Self {
-        self.0
-            .insert_sorted("PageSize".into(), page_size.into());
-        self
-    }
-    pub fn set_product_type(mut self, product_type: &str) -> Self {
-        self.0
-            .insert_sorted("ProductType".into(), product_type.into());
-        self
+impl Paginated for QueryOrders {
+    type Item = Order;
+
+    fn with_page(self, page: &Page) -> Self {
+        match page {
+            Page::Number(number) => self.set_page_num(*number as i64),
+            Page::Token(_) => self,
+        }
     }
-    pub fn set_subscription_type(mut self, subscription_type: &str) -> Self {
-        self.0
-            .insert_sorted("SubscriptionType".into(), subscription_type.into());
-        self
+    fn next_page(&self, page: &Page, response: &Self::Response) -> Option<Page> {
+        next_number(page, self.total_pages(response)?)
     }
-    pub fn set_payment_status(mut self, payment_status: &str) -> Self {
-        self.0
-            .insert_sorted("PaymentStatus".into(), payment_status.into());
-        self
+    fn total_pages(&self, response: &Self::Response) -> Option<u64> {
+        Some(page_count(
+            response.data.total_count as u64,
+            response.data.page_size as u64,
+        ))
     }
-    pub fn set_order_type(mut self, order_type: &str) -> Self {
-        self.0
-            .insert_sorted("OrderType".into(), order_type.into());
-        self
+    fn into_items(response: Self::Response) -> Vec<Self::Item> {
+        response.data.order_list.order
     }
 }
 
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
 pub struct QueryOrdersResponse {
     pub message: String,
     pub request_id: String,
@@ -66,8 +65,8 @@ pub struct QueryOrdersResponse {
     pub success: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Data {
     pub total_count: f64,
     pub page_num: i64,
@@ -75,14 +74,14 @@ pub struct Data {
     pub order_list: OrderList,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
 pub struct OrderList {
     pub order: Vec<Order>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Order {
     pub subscription_type: String,
     pub pretax_gross_amount: f64,
@@ -98,21 +97,55 @@ pub struct Order {
     pub payment_status: String,
 }
 
-
-
 #[cfg(test)]
 mod tests {
+    use crate::aliapis::sign::api_tests;
     use crate::Api;
+    use chrono::{TimeZone, Utc};
 
-    use super::QueryOrders;
+    use super::{QueryOrders, QueryOrdersResponse};
 
-    #[tokio::test]
-    async fn works() -> anyhow::Result<()> {
-        let billing_cycle = chrono::Local::now().format("%Y-%m").to_string();
-        let test_api = QueryOrders::new().set_create_time_end("2024-01-01T00:00:00Z").set_page_size("300");
-        let response = test_api.send().await?;
+    #[test]
+    fn missing_fields() {
+        // 返回中省略的字段使用默认值
+        let response: QueryOrdersResponse = serde_json::from_str(
+            r#"{"Code":"Success","Data":{"OrderList":{"Order":[{"OrderId":"202401010001"}]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(response.data.order_list.order[0].order_id, "202401010001");
+        assert_eq!(response.data.order_list.order[0].payment_time, None);
+        assert!(!response.success);
+    }
 
-        assert_eq!(response.status(), 200);
-        Ok(())
+    api_tests! {
+        snapshot: QueryOrders::new()
+            .set_create_time_end(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+            .set_page_size(300),
+        canonical_request: "GET\n\
+             /\n\
+             CreateTimeEnd=2024-01-01T00%3A00%3A00Z&PageSize=300\n\
+             host:business.aliyuncs.com\n\
+             x-acs-action:QueryOrders\n\
+             x-acs-content-sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\n\
+             x-acs-date:2024-01-01T00:00:00Z\n\
+             x-acs-signature-nonce:3156853299f313e23d1673dc12e1703d\n\
+             x-acs-version:2017-12-14\n\
+             \n\
+             host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version\n\
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        signature: "b797f419aebab0c3156dfba8979b997ef9279dc0045a064cb98eea851bea754e",
+        cassette: "billing/query_orders" => QueryOrders::new()
+            .set_create_time_end(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+            .set_page_size(300),
+        response: |response| {
+            assert!(response.success);
+            assert_eq!(response.request_id, "D92B4F18-5E7A-5C31-B6D9-0A2E8C4F7B13");
+            assert_eq!((response.data.page_num, response.data.page_size), (1, 300));
+            assert_eq!(response.data.total_count, 1.0);
+            let order = &response.data.order_list.order[0];
+            assert_eq!(order.order_id, "2023123100000001");
+            assert_eq!(order.order_type, "Renew");
+            assert_eq!(order.payment_status, "Paid");
+        },
     }
 } 
\ No newline at end of file
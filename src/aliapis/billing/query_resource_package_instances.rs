@@ -1,77 +1,58 @@
-use crate::aliapis::sign::Api;
-use crate::aliapis::sign::RequestHeader;
-use ordermap::OrderMap;
+use api_derive::Api;
+use chrono::{DateTime, Utc};
+use crate::aliapis::paginate::{next_number, page_count, Page, Paginated};
 use serde::Deserialize;
 use serde::Serialize;
 
-
-#[derive(Debug, Clone)]
-pub struct QueryResourcePackageInstances(OrderMap<String, String>);
-
-impl QueryResourcePackageInstances {
+#[derive(Debug, Clone, Api)]
+#[api(default(PageSize = 300))]
+pub struct QueryResourcePackageInstances {
     ///产品代码。
-    pub fn set_product_code(mut self, product_code: &str) -> Self {
-        self.0
-            .insert_sorted("ProductCode".into(), product_code.into());
-        self
-    }
-    ///失效起始时间。日期格式按照 ISO8601 标准表示，并需要使用 UTC 时间。格式为：yyyy-MM-ddTHH:mm:ssZ。
-    pub fn set_expiry_time_start(mut self, expiry_time_start: &str) -> Self {
-        self.0
-            .insert_sorted("ExpiryTimeStart".into(), expiry_time_start.into());
-        self
-    }
-    ///失效结束时间。日期格式按照 ISO8601 标准表示，并需要使用 UTC 时间。格式为：yyyy-MM-ddTHH:mm:ssZ。
-    pub fn set_expiry_time_end(mut self, expiry_time_end: &str) -> Self {
-        self.0
-            .insert_sorted("ExpiryTimeEnd".into(), expiry_time_end.into());
-        self
-    }
+    #[api(name = "ProductCode")]
+    product_code: Option<String>,
+    ///失效起始时间，按 ISO8601 格式的 UTC 时间发送。
+    #[api(name = "ExpiryTimeStart")]
+    expiry_time_start: Option<DateTime<Utc>>,
+    ///失效结束时间，按 ISO8601 格式的 UTC 时间发送。
+    #[api(name = "ExpiryTimeEnd")]
+    expiry_time_end: Option<DateTime<Utc>>,
     ///是否包含合作伙伴。
-    pub fn set_include_partner(mut self, include_partner: &str) -> Self {
-        self.0
-            .insert_sorted("IncludePartner".into(), include_partner.into());
-        self
-    }
+    #[api(name = "IncludePartner")]
+    include_partner: Option<bool>,
     ///页码，默认为 1。
-    pub fn set_page_num(mut self, page_num: &str) -> Self {
-        self.0.insert_sorted("PageNum".into(), page_num.into());
-        self
-    }
+    #[api(name = "PageNum")]
+    page_num: Option<i64>,
     ///每页条数，默认值 20，最大 300。
-    pub fn set_page_size(mut self, page_size: &str) -> Self {
-        self.0.insert_sorted("PageSize".into(), page_size.into());
-        self
-    }
+    #[api(name = "PageSize")]
+    page_size: Option<i64>,
 }
 
-impl Api for QueryResourcePackageInstances {
-    fn new() -> Self {
-        let mut parameters = OrderMap::new();
-        parameters.insert_sorted("PageSize".to_string(), "300".to_string());
+impl Paginated for QueryResourcePackageInstances {
+    type Item = Instance;
 
-        QueryResourcePackageInstances(parameters)
+    fn with_page(self, page: &Page) -> Self {
+        match page {
+            Page::Number(number) => self.set_page_num(*number as i64),
+            Page::Token(_) => self,
+        }
     }
-
-    fn name(&self) -> String {
-        "QueryResourcePackageInstances".to_string()
+    fn next_page(&self, page: &Page, response: &Self::Response) -> Option<Page> {
+        next_number(page, self.total_pages(response)?)
     }
-
-    fn send(self) -> impl std::future::Future<Output = Result<reqwest::Response, reqwest::Error>> {
-        RequestHeader::new(
-            super::ENDPOINT.to_string(),
-            self.name(),
-            super::VERSION.to_string(),
-            self.0,
-        )
-        .sign()
-        .send()
+    fn total_pages(&self, response: &Self::Response) -> Option<u64> {
+        Some(page_count(
+            response.data.total_count as u64,
+            response.data.page_size as u64,
+        ))
+    }
+    fn into_items(response: Self::Response) -> Vec<Self::Item> {
+        response.data.instances.instance
     }
 }
 
 // Response Struct
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct QueryResourcePackageInstancesResponse {
     pub request_id: String,
     pub message: String,
@@ -81,7 +62,7 @@ pub struct QueryResourcePackageInstancesResponse {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Data {
     pub instances: Instances,
     pub total_count: i64,
@@ -91,13 +72,13 @@ pub struct Data {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Instances {
     pub instance: Vec<Instance>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Instance {
     pub status: String,
     pub instance_id: String,
@@ -116,23 +97,42 @@ pub struct Instance {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct ApplicableProducts {
     pub product: Vec<String>,
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::aliapis::sign::api_tests;
     use crate::aliapis::{
         billing::query_resource_package_instances::QueryResourcePackageInstances, sign::Api,
     };
 
-    #[tokio::test]
-    async fn works() -> anyhow::Result<()> {
-        let test_api = QueryResourcePackageInstances::new();
-        let response = test_api.send().await?;
-
-        assert_eq!(response.status(), 200);
-        Ok(())
+    api_tests! {
+        snapshot: QueryResourcePackageInstances::new(),
+        canonical_request: "GET\n\
+             /\n\
+             PageSize=300\n\
+             host:business.aliyuncs.com\n\
+             x-acs-action:QueryResourcePackageInstances\n\
+             x-acs-content-sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\n\
+             x-acs-date:2024-01-01T00:00:00Z\n\
+             x-acs-signature-nonce:3156853299f313e23d1673dc12e1703d\n\
+             x-acs-version:2017-12-14\n\
+             \n\
+             host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version\n\
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        signature: "80de5abb3025255cff2b00e1f0a8dc68373d6e10ffb6208024359f5942142609",
+        cassette: "billing/query_resource_package_instances" => QueryResourcePackageInstances::new(),
+        response: |response| {
+            assert!(response.success);
+            assert_eq!(response.request_id, "6C3A8E5D-1F9B-5742-A0C8-3E7D5B9F1A86");
+            assert_eq!(response.data.total_count, 1);
+            let instance = &response.data.instances.instance[0];
+            assert_eq!(instance.instance_id, "OSSBAG-cn-example0001");
+            assert_eq!(instance.remaining_amount, "40");
+            assert_eq!(instance.applicable_products.product, ["oss"]);
+        },
     }
 }
@@ -0,0 +1,21 @@
+use crate::aliapis::param::param_enum;
+
+param_enum! {
+    /// 账单的颗粒度
+    pub enum Granularity {
+        ///月，与费用中心-费用账单-账单明细-账期账单一致
+        Monthly = "MONTHLY",
+        ///日，与费用中心-费用账单-账单明细-按天账单一致
+        Daily = "DAILY",
+    }
+}
+
+param_enum! {
+    /// 订阅类型
+    pub enum SubscriptionType {
+        ///预付费
+        Subscription = "Subscription",
+        ///后付费
+        PayAsYouGo = "PayAsYouGo",
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::OnceLock;
+
+use tokio::runtime::{Builder, Runtime};
+
+use crate::aliapis::{
+    client::{AliClient, AliClientBuilder},
+    error::AliError,
+    prepare::PreparedRequest,
+    sign::Api,
+    transport::HttpResponse,
+};
+
+/// 同步客户端，需要开启 `blocking` feature
+///
+/// 在内部的单线程 tokio runtime 上执行 `AliClient` 的请求，调用方不需要自己创建 runtime。
+/// 不能在异步上下文中调用，否则会 panic，异步代码中直接使用 `AliClient`
+pub struct BlockingClient {
+    inner: AliClient,
+    runtime: Runtime,
+}
+
+impl BlockingClient {
+    ///包装已经配置好的 `AliClient`，连接池只在这个客户端内部的 runtime 上使用，
+    ///创建 runtime 失败时返回 `AliError::Runtime`
+    pub fn new(client: AliClient) -> Result<Self, AliError> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(AliError::Runtime)?;
+        Ok(BlockingClient {
+            inner: client,
+            runtime,
+        })
+    }
+
+    ///默认地域
+    pub fn region_id(&self) -> Option<&str> {
+        self.inner.region_id()
+    }
+
+    ///校验参数后签名并发送请求，阻塞到返回或重试结束
+    pub fn execute<A: Api>(&self, api: A) -> Result<HttpResponse, AliError> {
+        self.runtime.block_on(self.inner.execute(api))
+    }
+
+    ///签名并发送请求，将返回解析为 `A::Response`
+    pub fn execute_typed<A: Api>(&self, api: A) -> Result<A::Response, AliError> {
+        self.runtime.block_on(self.inner.execute_typed(api))
+    }
+
+    ///校验参数并签名，返回签名后的请求而不发送
+    pub fn prepare<A: Api>(&self, api: A) -> Result<PreparedRequest, AliError> {
+        self.runtime.block_on(self.inner.prepare(api))
+    }
+}
+
+impl AliClientBuilder {
+    ///创建同步客户端，需要开启 `blocking` feature
+    pub fn build_blocking(self) -> Result<BlockingClient, AliError> {
+        BlockingClient::new(self.build()?)
+    }
+}
+
+/// `Api::send_blocking` 使用的进程内共享客户端
+///
+/// 与 `default_client` 分开创建，连接池不会跨 runtime 使用；创建失败时返回错误，下次调用时重新创建
+pub fn default_blocking_client() -> Result<&'static BlockingClient, AliError> {
+    static DEFAULT_CLIENT: OnceLock<BlockingClient> = OnceLock::new();
+    if let Some(client) = DEFAULT_CLIENT.get() {
+        return Ok(client);
+    }
+    let client = BlockingClient::new(AliClient::default())?;
+    Ok(DEFAULT_CLIENT.get_or_init(|| client))
+}
+
+// 测试使用账单和 DNS 的接口
+#[cfg(all(test, feature = "billing", feature = "dns"))]
+mod tests {
+    use super::BlockingClient;
+    use crate::aliapis::{
+        billing::{QueryAccountBalance, QueryAccountBalanceResponse},
+        dns::AddDomainRecord,
+        error::AliError,
+        sign::Api,
+        transport::cassette_client,
+    };
+
+    // 普通的 #[test]，调用方没有 runtime
+    #[test]
+    fn execute() -> anyhow::Result<()> {
+        let client = BlockingClient::new(cassette_client("billing/query_account_balance"))?;
+        let response = client.execute(QueryAccountBalance::new())?;
+        assert_eq!(response.status(), 200);
+
+        let client = BlockingClient::new(cassette_client("billing/query_account_balance"))?;
+        let balance: QueryAccountBalanceResponse =
+            client.execute_typed(QueryAccountBalance::new())?;
+        assert!(balance.success);
+
+        let prepared = client.prepare(QueryAccountBalance::new())?;
+        assert_eq!(prepared.url, "https://business.aliyuncs.com/");
+        Ok(())
+    }
+
+    #[test]
+    fn validate() -> anyhow::Result<()> {
+        let client = BlockingClient::new(cassette_client("dns/add_domain_record"))?;
+        let error = client.execute(AddDomainRecord::new()).unwrap_err();
+        assert!(matches!(error, AliError::Validation(_)));
+        Ok(())
+    }
+}
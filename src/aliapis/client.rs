@@ -0,0 +1,391 @@
+use std::{sync::Arc, sync::OnceLock, time::Duration};
+
+use tracing::debug;
+
+use crate::aliapis::{
+    credential::{CredentialChain, CredentialProvider, RefreshingProvider},
+    endpoint::{EndpointResolver, DEFAULT_REGION},
+    error::{AliError, ServiceError},
+    prepare::PreparedRequest,
+    retry::RetryPolicy,
+    sign::{Api, Clock, NonceGenerator, RequestHeader, SignatureAlgorithm, SystemClock, UuidNonce},
+    transport::{HttpRequest, HttpResponse, HttpTransport, ReqwestTransport, TransportFuture},
+};
+
+/// 阿里云 OpenAPI 客户端
+///
+/// 持有传输层（默认为复用连接池的 `reqwest::Client`）、凭证和地域等配置，可在多个任务间共享
+#[derive(Clone)]
+pub struct AliClient {
+    http: Arc<dyn HttpTransport>,
+    credentials: Arc<dyn CredentialProvider>,
+    region_id: Option<String>,
+    endpoints: EndpointResolver,
+    retry: RetryPolicy,
+    algorithm: Option<SignatureAlgorithm>,
+    clock: Arc<dyn Clock>,
+    nonce: Arc<dyn NonceGenerator>,
+}
+
+impl Default for AliClient {
+    fn default() -> Self {
+        let mut builder = AliClient::builder();
+        let http: Arc<dyn HttpTransport> = match builder.http_client() {
+            Ok(client) => Arc::new(ReqwestTransport::new(client)),
+            Err(e) => {
+                debug!("failed to build http client: {}, retry when sending", e);
+                Arc::new(DeferredTransport::default())
+            }
+        };
+        builder.finish(http)
+    }
+}
+
+// 默认配置构建 reqwest 客户端失败时使用（例如 TLS 后端初始化失败），发送时重新构建，仍然失败则返回构建错误
+#[derive(Default)]
+struct DeferredTransport(tokio::sync::OnceCell<ReqwestTransport>);
+
+impl HttpTransport for DeferredTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let transport = self
+                .0
+                .get_or_try_init(|| async {
+                    AliClient::builder()
+                        .http_client()
+                        .map(ReqwestTransport::new)
+                })
+                .await?;
+            transport.send(request).await
+        })
+    }
+}
+
+impl AliClient {
+    ///使用默认配置创建客户端
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn builder() -> AliClientBuilder {
+        AliClientBuilder::default()
+    }
+
+    ///默认地域
+    pub fn region_id(&self) -> Option<&str> {
+        self.region_id.as_deref()
+    }
+
+    ///校验参数后签名并发送请求，非 2xx 的返回会被解析为 `AliError`，按重试策略重试
+    pub async fn execute<A: Api>(&self, api: A) -> Result<HttpResponse, AliError> {
+        let request = self.unsigned(api)?;
+        let mut attempt = 1;
+        loop {
+            let stamped = request
+                .clone()
+                .stamp_with(self.clock.as_ref(), self.nonce.as_ref());
+            match self.send_once(stamped).await {
+                Err(e) if self.retry.should_retry(attempt, &e, request.idempotent) => {
+                    let delay = self.retry.backoff(attempt);
+                    debug!("attempt {} failed: {}, retry in {:?}", attempt, e, delay);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    ///校验参数并按客户端的配置签名，返回签名后的请求而不发送，用于排查签名问题或在其他环境重放
+    pub async fn prepare<A: Api>(&self, api: A) -> Result<PreparedRequest, AliError> {
+        let request = self
+            .unsigned(api)?
+            .stamp_with(self.clock.as_ref(), self.nonce.as_ref());
+        let credential = self.credentials.get_credential().await?;
+        let request = request.sign_with(&credential)?;
+        PreparedRequest::new(request, &credential)
+    }
+
+    // 校验参数，解析 host、填充地域参数并设置签名算法，得到待签名的请求
+    fn unsigned<A: Api>(&self, api: A) -> Result<RequestHeader, AliError> {
+        api.validate()?;
+        let request = self.fill_region(self.resolve_host(api.canonical_request()));
+        Ok(match self.algorithm {
+            Some(algorithm) => request.set_algorithm(algorithm),
+            None => request,
+        })
+    }
+
+    async fn send_once(&self, request: RequestHeader) -> Result<HttpResponse, AliError> {
+        let request = request.sign(self.credentials.as_ref()).await?;
+        let response = self.http.send(request.into_http_request()?).await?;
+        if (200..300).contains(&response.status) {
+            return Ok(response);
+        }
+
+        Err(AliError::from_service(ServiceError::from_response(
+            response.status,
+            &response.headers,
+            &response.body,
+        )))
+    }
+
+    ///签名并发送请求，将返回解析为 `A::Response`
+    pub async fn execute_typed<A: Api>(&self, api: A) -> Result<A::Response, AliError> {
+        self.execute(api).await?.json()
+    }
+
+    // 按产品 code 和客户端的地域解析 host，没有产品 code 的请求保留原来的 host
+    fn resolve_host(&self, request: RequestHeader) -> RequestHeader {
+        match &request.product {
+            Some(product) => {
+                let region_id = self.region_id.as_deref().unwrap_or(DEFAULT_REGION);
+                let host = match &request.host_prefix {
+                    Some(prefix) => {
+                        format!("{}.{}", prefix, self.endpoints.resolve(product, region_id))
+                    }
+                    None => self.endpoints.resolve(product, region_id),
+                };
+                request.set_host(&host)
+            }
+            None => request,
+        }
+    }
+
+    // 接口没有指定地域时使用客户端的地域，与解析 host 使用的地域保持一致
+    fn fill_region(&self, mut request: RequestHeader) -> RequestHeader {
+        if let Some(name) = &request.region_param {
+            let region_id = self.region_id.as_deref().unwrap_or(DEFAULT_REGION);
+            request
+                .query_param
+                .insert_sorted(name.clone(), region_id.to_string());
+        }
+        request
+    }
+}
+
+/// `Api::send` 使用的进程内共享客户端
+pub fn default_client() -> &'static AliClient {
+    static DEFAULT_CLIENT: OnceLock<AliClient> = OnceLock::new();
+    DEFAULT_CLIENT.get_or_init(AliClient::default)
+}
+
+#[derive(Default)]
+pub struct AliClientBuilder {
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<reqwest::Proxy>,
+    user_agent: Option<String>,
+    transport: Option<Arc<dyn HttpTransport>>,
+    credentials: Option<Arc<dyn CredentialProvider>>,
+    region_id: Option<String>,
+    endpoints: EndpointResolver,
+    retry: Option<RetryPolicy>,
+    algorithm: Option<SignatureAlgorithm>,
+    clock: Option<Arc<dyn Clock>>,
+    nonce: Option<Arc<dyn NonceGenerator>>,
+}
+
+impl AliClientBuilder {
+    ///请求总超时
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+    ///建立连接超时
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+    ///使用代理
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+    ///设置 User-Agent，默认为 libaliopenapi/{version}
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+    ///替换传输层，例如 `ReplayTransport`，设置后超时、代理和 User-Agent 配置不再生效
+    pub fn transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+    ///设置凭证来源，默认为带自动刷新的 `CredentialChain`
+    pub fn credentials(mut self, credentials: impl CredentialProvider + 'static) -> Self {
+        self.credentials = Some(Arc::new(credentials));
+        self
+    }
+    ///设置默认地域，默认为 cn-hangzhou
+    pub fn region_id(mut self, region_id: impl Into<String>) -> Self {
+        self.region_id = Some(region_id.into());
+        self
+    }
+    ///设置 endpoint 解析规则，例如使用 VPC 网络
+    pub fn endpoint_resolver(mut self, endpoints: EndpointResolver) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+    ///将产品的 endpoint 替换为指定的 host，例如国际站或专有云 endpoint
+    pub fn endpoint(mut self, product: &str, host: impl Into<String>) -> Self {
+        self.endpoints = self.endpoints.endpoint(product, host);
+        self
+    }
+    ///设置重试策略，默认为 `RetryPolicy::default()`
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+    ///设置签名算法，覆盖各接口的默认值（ACS3-HMAC-SHA256）
+    pub fn signature_algorithm(mut self, algorithm: SignatureAlgorithm) -> Self {
+        self.algorithm = Some(algorithm);
+        self
+    }
+    ///设置 x-acs-date 的时间来源，默认为系统时间
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+    ///设置 x-acs-signature-nonce 的来源，默认为随机 UUID
+    pub fn nonce_generator(mut self, nonce: impl NonceGenerator + 'static) -> Self {
+        self.nonce = Some(Arc::new(nonce));
+        self
+    }
+
+    pub fn build(mut self) -> Result<AliClient, reqwest::Error> {
+        let http = match self.transport.take() {
+            Some(transport) => transport,
+            None => Arc::new(ReqwestTransport::new(self.http_client()?)),
+        };
+        Ok(self.finish(http))
+    }
+
+    // 按超时、代理和 User-Agent 配置构建 reqwest 客户端
+    fn http_client(&mut self) -> Result<reqwest::Client, reqwest::Error> {
+        let mut http = reqwest::Client::builder().user_agent(
+            self.user_agent
+                .take()
+                .unwrap_or_else(|| format!("libaliopenapi/{}", env!("CARGO_PKG_VERSION"))),
+        );
+        if let Some(timeout) = self.timeout {
+            http = http.timeout(timeout);
+        }
+        if let Some(connect_timeout) = self.connect_timeout {
+            http = http.connect_timeout(connect_timeout);
+        }
+        if let Some(proxy) = self.proxy.take() {
+            http = http.proxy(proxy);
+        }
+        http.build()
+    }
+
+    fn finish(self, http: Arc<dyn HttpTransport>) -> AliClient {
+        AliClient {
+            http,
+            credentials: self
+                .credentials
+                .unwrap_or_else(|| Arc::new(RefreshingProvider::new(CredentialChain::default()))),
+            region_id: self.region_id,
+            endpoints: self.endpoints,
+            retry: self.retry.unwrap_or_default(),
+            algorithm: self.algorithm,
+            clock: self.clock.unwrap_or_else(|| Arc::new(SystemClock)),
+            nonce: self.nonce.unwrap_or_else(|| Arc::new(UuidNonce)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ordermap::OrderMap;
+
+    use super::AliClient;
+    use crate::aliapis::{
+        endpoint::{EndpointResolver, NetworkType},
+        sign::RequestHeader,
+    };
+
+    fn unsigned(product: &str) -> RequestHeader {
+        RequestHeader::with_product(
+            product,
+            "DescribeDomains".to_string(),
+            "2015-01-09".to_string(),
+            OrderMap::new(),
+        )
+    }
+
+    #[test]
+    fn resolve_host() -> anyhow::Result<()> {
+        let client = AliClient::builder()
+            .region_id("cn-shanghai")
+            .endpoint("BssOpenApi", "business.ap-southeast-1.aliyuncs.com")
+            .build()?;
+
+        let request = client.resolve_host(unsigned("Alidns"));
+        assert_eq!(request.host, "alidns.cn-shanghai.aliyuncs.com");
+        assert_eq!(request.headers["host"], "alidns.cn-shanghai.aliyuncs.com");
+
+        let request = client.resolve_host(unsigned("BssOpenApi"));
+        assert_eq!(request.host, "business.ap-southeast-1.aliyuncs.com");
+
+        let request = client.resolve_host(unsigned("Dysmsapi"));
+        assert_eq!(request.host, "dysmsapi.aliyuncs.com");
+
+        // 没有产品 code 的请求保留原来的 host
+        let request = client.resolve_host(RequestHeader::new(
+            "ecs.cn-hangzhou.aliyuncs.com".to_string(),
+            "DescribeRegions".to_string(),
+            "2014-05-26".to_string(),
+            OrderMap::new(),
+        ));
+        assert_eq!(request.host, "ecs.cn-hangzhou.aliyuncs.com");
+
+        let client = AliClient::builder()
+            .endpoint_resolver(EndpointResolver::new().network(NetworkType::Vpc))
+            .build()?;
+        let request = client.resolve_host(unsigned("Alidns"));
+        assert_eq!(request.host, "alidns-vpc.cn-hangzhou.aliyuncs.com");
+
+        // SLS 的 project 作为 host 前缀保留
+        let request = client.resolve_host(unsigned("Sls").set_host_prefix("demo"));
+        assert_eq!(request.host, "demo.cn-hangzhou-intranet.log.aliyuncs.com");
+        Ok(())
+    }
+
+    #[cfg(feature = "arms")]
+    #[tokio::test]
+    async fn region_param() -> anyhow::Result<()> {
+        use crate::aliapis::{
+            arms::{list_synthetic_detail::ListSyntheticDetail, SyntheticDetail},
+            credential::StaticProvider,
+            sign::Api,
+        };
+
+        let api = || {
+            ListSyntheticDetail::new()
+                .set_synthetic_type(2)
+                .set_detail(SyntheticDetail::All)
+                .set_start_time(chrono::DateTime::UNIX_EPOCH)
+                .set_end_time(chrono::DateTime::UNIX_EPOCH)
+                .set_filters("f124d885bcbc4d78a7fcb3a020b6ad66")
+        };
+        let client = AliClient::builder()
+            .region_id("cn-shanghai")
+            .credentials(StaticProvider::new("ak", "sk"))
+            .build()?;
+
+        // 未设置 RegionId 时与 host 使用相同的地域
+        let request = client.prepare(api()).await?;
+        assert!(request
+            .url
+            .starts_with("https://arms.cn-shanghai.aliyuncs.com/?"));
+        assert!(request.url.contains("RegionId=cn-shanghai"));
+        assert!(request.canonical_request.contains("RegionId=cn-shanghai"));
+
+        // 显式设置的 RegionId 保持不变
+        let request = client.prepare(api().set_region_id("cn-beijing")).await?;
+        assert!(request.url.contains("RegionId=cn-beijing"));
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+//! 与 derive(Api) 一起编译 aliopenapi-codegen 的快照，生成的代码与派生宏不一致时测试编译失败
+#![allow(dead_code, unused_imports)]
+
+#[path = "../../crates/aliopenapi-codegen/fixtures/snapshots/alidns_mod.rs"]
+mod alidns;
+
+mod dysmsapi {
+    const VERSION: &str = "2017-05-25";
+    const PRODUCT: &str = "Dysmsapi";
+
+    pub mod query_send_statistics {
+        include!("../../crates/aliopenapi-codegen/fixtures/snapshots/query_send_statistics.rs");
+    }
+}
+
+mod tests {
+    use super::{alidns, dysmsapi::query_send_statistics};
+    use crate::aliapis::sign::Api;
+
+    #[test]
+    fn canonical_request() {
+        let request = alidns::AddDomainRecord::new()
+            .set_domain_name("example.com")
+            .set_rr("www")
+            .set_type("A")
+            .set_value("127.0.0.1")
+            .canonical_request();
+        assert_eq!(request.host, "alidns.cn-hangzhou.aliyuncs.com");
+        assert_eq!(request.headers["x-acs-action"], "AddDomainRecord");
+        assert_eq!(request.headers["x-acs-version"], "2015-01-09");
+        assert_eq!(
+            request.canonical_query_string(),
+            "DomainName=example.com&RR=www&Type=A&Value=127.0.0.1"
+        );
+
+        let error = alidns::AddDomainRecord::new().validate().unwrap_err();
+        assert_eq!(error.problems.len(), 4);
+
+        let request = query_send_statistics::QuerySendStatistics::new()
+            .set_is_globe(1)
+            .set_start_date("20240101")
+            .set_end_date("20240101")
+            .set_page_index(1)
+            .canonical_request();
+        assert_eq!(request.http_method, "POST");
+        assert_eq!(request.host, "dysmsapi.aliyuncs.com");
+        assert_eq!(
+            request.body.to_bytes(),
+            b"EndDate=20240101&IsGlobe=1&PageIndex=1&StartDate=20240101"
+        );
+
+        let response: alidns::DescribeDomainRecordsResponse =
+            serde_json::from_str(r#"{"TotalCount":1,"DomainRecords":{"Record":[{"RR":"www"}]}}"#)
+                .unwrap();
+        assert_eq!(response.domain_records.record[0].rr, "www");
+    }
+}
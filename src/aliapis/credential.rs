@@ -0,0 +1,750 @@
+use std::{
+    env, fmt,
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tokio::sync::Mutex;
+
+/// ECS 实例元数据服务默认地址
+pub const ECS_METADATA_BASE_URL: &str = "http://100.100.100.200";
+
+/// 阿里云访问凭证，STS 临时凭证额外携带 security token 和过期时间
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credential {
+    pub access_key_id: String,
+    pub access_key_secret: String,
+    pub security_token: Option<String>,
+    pub expiration: Option<DateTime<Utc>>,
+}
+
+impl Credential {
+    pub fn new(access_key_id: impl Into<String>, access_key_secret: impl Into<String>) -> Self {
+        Self {
+            access_key_id: access_key_id.into(),
+            access_key_secret: access_key_secret.into(),
+            security_token: None,
+            expiration: None,
+        }
+    }
+    ///设置 STS security token
+    pub fn with_security_token(mut self, security_token: impl Into<String>) -> Self {
+        self.security_token = Some(security_token.into());
+        self
+    }
+    ///设置过期时间
+    pub fn with_expiration(mut self, expiration: DateTime<Utc>) -> Self {
+        self.expiration = Some(expiration);
+        self
+    }
+    ///凭证是否会在 `window` 内过期，没有过期时间的凭证永不过期，`window` 超出可表示的时间范围时视为会过期
+    pub fn expires_within(&self, window: Duration) -> bool {
+        match self.expiration {
+            Some(expiration) => chrono::Duration::from_std(window)
+                .ok()
+                .and_then(|window| Utc::now().checked_add_signed(window))
+                .is_none_or(|deadline| deadline >= expiration),
+            None => false,
+        }
+    }
+}
+
+// 避免在日志中输出 SK 和 token
+impl fmt::Debug for Credential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credential")
+            .field("access_key_id", &self.access_key_id)
+            .field("access_key_secret", &"******")
+            .field(
+                "security_token",
+                &self.security_token.as_ref().map(|_| "******"),
+            )
+            .field("expiration", &self.expiration)
+            .finish()
+    }
+}
+
+/// 获取凭证失败的原因
+#[derive(Debug)]
+pub enum CredentialError {
+    /// 当前 provider 没有可用的凭证
+    NotFound(String),
+    /// 读取配置文件失败
+    Io(std::io::Error),
+    /// 配置文件或元数据格式错误
+    Parse(String),
+    /// 请求实例元数据失败
+    Http(reqwest::Error),
+    /// 凭证链中所有 provider 都失败，按顺序记录每个 provider 的失败原因
+    Exhausted(Vec<(String, CredentialError)>),
+}
+
+impl fmt::Display for CredentialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialError::NotFound(msg) => write!(f, "credential not found: {}", msg),
+            CredentialError::Io(e) => write!(f, "failed to read credential file: {}", e),
+            CredentialError::Parse(msg) => write!(f, "invalid credential: {}", msg),
+            CredentialError::Http(e) => write!(f, "failed to fetch credential: {}", e),
+            CredentialError::Exhausted(errors) => {
+                write!(f, "no credential provider in the chain succeeded")?;
+                for (name, e) in errors {
+                    write!(f, "; {}: {}", name, e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for CredentialError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CredentialError::Io(e) => Some(e),
+            CredentialError::Http(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for CredentialError {
+    fn from(e: std::io::Error) -> Self {
+        CredentialError::Io(e)
+    }
+}
+
+impl From<reqwest::Error> for CredentialError {
+    fn from(e: reqwest::Error) -> Self {
+        CredentialError::Http(e)
+    }
+}
+
+pub type CredentialFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Credential, CredentialError>> + Send + 'a>>;
+
+/// 凭证来源
+pub trait CredentialProvider: Send + Sync {
+    /// provider 名称，用于错误信息
+    fn name(&self) -> &str;
+    /// 获取凭证
+    fn get_credential(&self) -> CredentialFuture<'_>;
+}
+
+/// 固定的 AK/SK
+#[derive(Debug, Clone)]
+pub struct StaticProvider {
+    credential: Credential,
+}
+
+impl StaticProvider {
+    pub fn new(access_key_id: impl Into<String>, access_key_secret: impl Into<String>) -> Self {
+        Self {
+            credential: Credential::new(access_key_id, access_key_secret),
+        }
+    }
+    ///使用 STS 临时凭证
+    pub fn with_security_token(mut self, security_token: impl Into<String>) -> Self {
+        self.credential = self.credential.with_security_token(security_token);
+        self
+    }
+}
+
+impl From<Credential> for StaticProvider {
+    fn from(credential: Credential) -> Self {
+        Self { credential }
+    }
+}
+
+impl CredentialProvider for StaticProvider {
+    fn name(&self) -> &str {
+        "static"
+    }
+
+    fn get_credential(&self) -> CredentialFuture<'_> {
+        let credential = self.credential.clone();
+        Box::pin(async move { Ok(credential) })
+    }
+}
+
+/// 从环境变量读取 AK/SK
+///
+/// * ALI_CLOUD_ACCESSKEY_ID
+/// * ALI_CLOUD_ACCESSKEY_SECRET
+/// * ALI_CLOUD_SECURITY_TOKEN（可选，使用 STS 临时凭证时设置）
+#[derive(Debug, Clone, Default)]
+pub struct EnvProvider;
+
+impl EnvProvider {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl CredentialProvider for EnvProvider {
+    fn name(&self) -> &str {
+        "env"
+    }
+
+    fn get_credential(&self) -> CredentialFuture<'_> {
+        Box::pin(async {
+            let access_key_id = env_non_empty("ALI_CLOUD_ACCESSKEY_ID")?;
+            let access_key_secret = env_non_empty("ALI_CLOUD_ACCESSKEY_SECRET")?;
+            let credential = Credential::new(access_key_id, access_key_secret);
+            match env_non_empty("ALI_CLOUD_SECURITY_TOKEN") {
+                Ok(security_token) => Ok(credential.with_security_token(security_token)),
+                Err(_) => Ok(credential),
+            }
+        })
+    }
+}
+
+fn env_non_empty(key: &str) -> Result<String, CredentialError> {
+    match env::var(key) {
+        Ok(v) if !v.is_empty() => Ok(v),
+        _ => Err(CredentialError::NotFound(format!("{} is not set", key))),
+    }
+}
+
+/// 从阿里云 CLI 的配置文件 `~/.aliyun/config.json` 读取凭证
+///
+/// 未指定 profile 时依次使用环境变量 ALI_CLOUD_PROFILE 和配置文件中的 `current`
+#[derive(Debug, Clone, Default)]
+pub struct ProfileProvider {
+    path: Option<PathBuf>,
+    profile: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProfileFile {
+    #[serde(default)]
+    current: String,
+    #[serde(default)]
+    profiles: Vec<Profile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Profile {
+    name: String,
+    #[serde(default)]
+    mode: String,
+    #[serde(default)]
+    access_key_id: String,
+    #[serde(default)]
+    access_key_secret: String,
+    #[serde(default)]
+    sts_token: String,
+}
+
+impl ProfileProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    ///设置配置文件路径
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+    ///设置使用的 profile 名称
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    fn config_path(&self) -> Result<PathBuf, CredentialError> {
+        if let Some(path) = &self.path {
+            return Ok(path.clone());
+        }
+        env::var_os("HOME")
+            .or_else(|| env::var_os("USERPROFILE"))
+            .map(|home| Path::new(&home).join(".aliyun").join("config.json"))
+            .ok_or_else(|| CredentialError::NotFound("home directory is unknown".into()))
+    }
+
+    async fn load(&self) -> Result<Credential, CredentialError> {
+        let path = self.config_path()?;
+        if !path.exists() {
+            return Err(CredentialError::NotFound(format!(
+                "{} does not exist",
+                path.display()
+            )));
+        }
+        let content = tokio::fs::read_to_string(&path).await?;
+        let file: ProfileFile = serde_json::from_str(&content)
+            .map_err(|e| CredentialError::Parse(format!("{}: {}", path.display(), e)))?;
+
+        let name = self
+            .profile
+            .clone()
+            .or_else(|| env::var("ALI_CLOUD_PROFILE").ok())
+            .unwrap_or(file.current);
+        let profile = file
+            .profiles
+            .into_iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| CredentialError::NotFound(format!("profile {} not found", name)))?;
+
+        match profile.mode.as_str() {
+            "AK" | "" => Ok(Credential::new(
+                profile.access_key_id,
+                profile.access_key_secret,
+            )),
+            "StsToken" => Ok(
+                Credential::new(profile.access_key_id, profile.access_key_secret)
+                    .with_security_token(profile.sts_token),
+            ),
+            mode => Err(CredentialError::Parse(format!(
+                "profile {} uses unsupported mode {}",
+                name, mode
+            ))),
+        }
+    }
+}
+
+impl CredentialProvider for ProfileProvider {
+    fn name(&self) -> &str {
+        "profile"
+    }
+
+    fn get_credential(&self) -> CredentialFuture<'_> {
+        Box::pin(self.load())
+    }
+}
+
+/// 通过 ECS 实例元数据服务获取 RAM 角色凭证
+#[derive(Debug, Clone)]
+pub struct EcsRamRoleProvider {
+    base_url: String,
+    role_name: Option<String>,
+    client: reqwest::Client,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EcsMetadataCredential {
+    code: String,
+    access_key_id: String,
+    access_key_secret: String,
+    security_token: String,
+    expiration: DateTime<Utc>,
+}
+
+impl Default for EcsRamRoleProvider {
+    fn default() -> Self {
+        Self {
+            base_url: ECS_METADATA_BASE_URL.to_string(),
+            role_name: None,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(1))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl EcsRamRoleProvider {
+    ///未指定角色名时从元数据服务读取实例绑定的角色
+    pub fn new() -> Self {
+        Self::default()
+    }
+    ///从环境变量 ALI_CLOUD_ECS_METADATA 读取角色名，未设置时返回 None
+    pub fn from_env() -> Option<Self> {
+        env::var("ALI_CLOUD_ECS_METADATA")
+            .ok()
+            .filter(|role| !role.is_empty())
+            .map(|role| Self::new().with_role_name(role))
+    }
+    ///设置 RAM 角色名
+    pub fn with_role_name(mut self, role_name: impl Into<String>) -> Self {
+        self.role_name = Some(role_name.into());
+        self
+    }
+    ///设置元数据服务地址，默认为 http://100.100.100.200
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    async fn fetch(&self) -> Result<Credential, CredentialError> {
+        let url = format!(
+            "{}/latest/meta-data/ram/security-credentials/",
+            self.base_url
+        );
+        let role_name = match &self.role_name {
+            Some(role_name) => role_name.clone(),
+            None => {
+                let role_name = self
+                    .client
+                    .get(&url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .text()
+                    .await?;
+                role_name.trim().to_string()
+            }
+        };
+        if role_name.is_empty() {
+            return Err(CredentialError::NotFound(
+                "no RAM role is attached to the instance".into(),
+            ));
+        }
+
+        let metadata: EcsMetadataCredential = self
+            .client
+            .get(format!("{}{}", url, role_name))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if metadata.code != "Success" {
+            return Err(CredentialError::Parse(format!(
+                "metadata service returned code {}",
+                metadata.code
+            )));
+        }
+        Ok(
+            Credential::new(metadata.access_key_id, metadata.access_key_secret)
+                .with_security_token(metadata.security_token)
+                .with_expiration(metadata.expiration),
+        )
+    }
+}
+
+impl CredentialProvider for EcsRamRoleProvider {
+    fn name(&self) -> &str {
+        "ecs_ram_role"
+    }
+
+    fn get_credential(&self) -> CredentialFuture<'_> {
+        Box::pin(self.fetch())
+    }
+}
+
+/// 按顺序尝试多个 provider，返回第一个成功的凭证
+///
+/// 默认顺序为：环境变量、`~/.aliyun/config.json`、ECS RAM 角色（仅在设置了 ALI_CLOUD_ECS_METADATA 时启用）
+pub struct CredentialChain {
+    providers: Vec<Box<dyn CredentialProvider>>,
+}
+
+impl CredentialChain {
+    pub fn new() -> Self {
+        Self {
+            providers: Vec::new(),
+        }
+    }
+    ///在链尾追加 provider
+    pub fn push(mut self, provider: impl CredentialProvider + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    async fn resolve(&self) -> Result<Credential, CredentialError> {
+        let mut errors = Vec::new();
+        for provider in &self.providers {
+            match provider.get_credential().await {
+                Ok(credential) => return Ok(credential),
+                Err(e) => errors.push((provider.name().to_string(), e)),
+            }
+        }
+        Err(CredentialError::Exhausted(errors))
+    }
+}
+
+impl Default for CredentialChain {
+    fn default() -> Self {
+        let chain = CredentialChain::new()
+            .push(EnvProvider::new())
+            .push(ProfileProvider::new());
+        match EcsRamRoleProvider::from_env() {
+            Some(ecs) => chain.push(ecs),
+            None => chain,
+        }
+    }
+}
+
+impl CredentialProvider for CredentialChain {
+    fn name(&self) -> &str {
+        "chain"
+    }
+
+    fn get_credential(&self) -> CredentialFuture<'_> {
+        Box::pin(self.resolve())
+    }
+}
+
+/// 缓存内部 provider 返回的凭证，并在过期前重新获取
+///
+/// 多个并发请求共享同一个实例时只会有一个请求触发刷新，其余请求等待刷新结果
+pub struct RefreshingProvider<P> {
+    inner: P,
+    refresh_before: Duration,
+    cached: Mutex<Option<Credential>>,
+}
+
+impl<P: CredentialProvider> RefreshingProvider<P> {
+    ///默认在过期前 5 分钟刷新
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            refresh_before: Duration::from_secs(5 * 60),
+            cached: Mutex::new(None),
+        }
+    }
+    ///设置提前刷新的时间
+    pub fn with_refresh_before(mut self, refresh_before: Duration) -> Self {
+        self.refresh_before = refresh_before;
+        self
+    }
+
+    async fn resolve(&self) -> Result<Credential, CredentialError> {
+        let mut cached = self.cached.lock().await;
+        if let Some(credential) = cached.as_ref() {
+            if !credential.expires_within(self.refresh_before) {
+                return Ok(credential.clone());
+            }
+        }
+        match self.inner.get_credential().await {
+            Ok(credential) => {
+                *cached = Some(credential.clone());
+                Ok(credential)
+            }
+            // 刷新失败时继续使用尚未过期的缓存凭证
+            Err(e) => match cached.as_ref() {
+                Some(credential) if !credential.expires_within(Duration::ZERO) => {
+                    Ok(credential.clone())
+                }
+                _ => Err(e),
+            },
+        }
+    }
+}
+
+impl<P: CredentialProvider> CredentialProvider for RefreshingProvider<P> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn get_credential(&self) -> CredentialFuture<'_> {
+        Box::pin(self.resolve())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    // 模拟元数据服务，按请求路径返回固定内容
+    async fn metadata_server(role: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 1024];
+                let n = stream.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let content = if path.ends_with("/security-credentials/") {
+                    role
+                } else {
+                    body
+                };
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    content.len(),
+                    content
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn ecs_ram_role() -> anyhow::Result<()> {
+        let base_url = metadata_server(
+            "test-role",
+            r#"{"AccessKeyId":"STS.id","AccessKeySecret":"secret","Expiration":"2024-01-01T00:00:00Z","SecurityToken":"token","LastUpdated":"2024-01-01T00:00:00Z","Code":"Success"}"#,
+        )
+        .await;
+        let provider = EcsRamRoleProvider::new().with_base_url(base_url);
+        let credential = provider.get_credential().await?;
+
+        assert_eq!(credential.access_key_id, "STS.id");
+        assert_eq!(credential.access_key_secret, "secret");
+        assert_eq!(credential.security_token.as_deref(), Some("token"));
+        assert_eq!(
+            credential.expiration,
+            Some("2024-01-01T00:00:00Z".parse::<DateTime<Utc>>()?)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn profile() -> anyhow::Result<()> {
+        let path = env::temp_dir().join(format!("aliyun-config-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            r#"{"current":"dev","profiles":[
+                {"name":"default","mode":"AK","access_key_id":"id1","access_key_secret":"sk1"},
+                {"name":"dev","mode":"AK","access_key_id":"id2","access_key_secret":"sk2"}]}"#,
+        )?;
+        let current = ProfileProvider::new()
+            .with_path(&path)
+            .get_credential()
+            .await?;
+        let default = ProfileProvider::new()
+            .with_path(&path)
+            .with_profile("default")
+            .get_credential()
+            .await?;
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(current, Credential::new("id2", "sk2"));
+        assert_eq!(default, Credential::new("id1", "sk1"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn chain_falls_through() -> anyhow::Result<()> {
+        let chain = CredentialChain::new()
+            .push(ProfileProvider::new().with_path("/nonexistent/config.json"))
+            .push(StaticProvider::new("id", "sk"));
+        assert_eq!(chain.get_credential().await?, Credential::new("id", "sk"));
+
+        let empty = CredentialChain::new()
+            .push(ProfileProvider::new().with_path("/nonexistent/config.json"));
+        assert!(matches!(
+            empty.get_credential().await,
+            Err(CredentialError::Exhausted(errors)) if errors.len() == 1
+        ));
+        Ok(())
+    }
+
+    // 每次调用返回一个新的凭证，过期时间由 ttl 决定
+    struct CountingProvider {
+        calls: std::sync::atomic::AtomicUsize,
+        ttl: chrono::Duration,
+    }
+
+    impl CredentialProvider for CountingProvider {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn get_credential(&self) -> CredentialFuture<'_> {
+            Box::pin(async move {
+                let n = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                Ok(Credential::new(format!("id{}", n), "sk")
+                    .with_security_token("token")
+                    .with_expiration(Utc::now() + self.ttl))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn refreshing() -> anyhow::Result<()> {
+        let provider = RefreshingProvider::new(CountingProvider {
+            calls: Default::default(),
+            ttl: chrono::Duration::hours(1),
+        });
+        let (a, b, c) = tokio::join!(
+            provider.get_credential(),
+            provider.get_credential(),
+            provider.get_credential()
+        );
+        assert_eq!(a?.access_key_id, "id0");
+        assert_eq!(b?.access_key_id, "id0");
+        assert_eq!(c?.access_key_id, "id0");
+
+        // 有效期短于提前刷新时间的凭证每次都会刷新
+        let provider = RefreshingProvider::new(CountingProvider {
+            calls: Default::default(),
+            ttl: chrono::Duration::minutes(1),
+        });
+        assert_eq!(provider.get_credential().await?.access_key_id, "id0");
+        assert_eq!(provider.get_credential().await?.access_key_id, "id1");
+        Ok(())
+    }
+
+    // 第一次调用返回凭证，之后的调用都失败
+    struct FlakyProvider {
+        calls: std::sync::atomic::AtomicUsize,
+        ttl: chrono::Duration,
+    }
+
+    impl CredentialProvider for FlakyProvider {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        fn get_credential(&self) -> CredentialFuture<'_> {
+            Box::pin(async move {
+                match self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+                    0 => Ok(Credential::new("id", "sk")
+                        .with_security_token("token")
+                        .with_expiration(Utc::now() + self.ttl)),
+                    _ => Err(CredentialError::NotFound("refresh failed".to_string())),
+                }
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn refresh_failure() -> anyhow::Result<()> {
+        // 已进入提前刷新窗口但尚未过期，刷新失败时沿用缓存
+        let provider = RefreshingProvider::new(FlakyProvider {
+            calls: Default::default(),
+            ttl: chrono::Duration::minutes(1),
+        });
+        assert_eq!(provider.get_credential().await?.access_key_id, "id");
+        assert_eq!(provider.get_credential().await?.access_key_id, "id");
+        assert_eq!(
+            provider
+                .inner
+                .calls
+                .load(std::sync::atomic::Ordering::SeqCst),
+            2
+        );
+
+        // 已过期的缓存不会被返回
+        let provider = RefreshingProvider::new(FlakyProvider {
+            calls: Default::default(),
+            ttl: chrono::Duration::minutes(-1),
+        });
+        provider.get_credential().await?;
+        assert!(matches!(
+            provider.get_credential().await,
+            Err(CredentialError::NotFound(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn refresh_before_overflow() -> anyhow::Result<()> {
+        let credential = Credential::new("id", "sk").with_expiration(Utc::now());
+        assert!(credential.expires_within(Duration::MAX));
+        assert!(!Credential::new("id", "sk").expires_within(Duration::MAX));
+
+        let provider = RefreshingProvider::new(CountingProvider {
+            calls: Default::default(),
+            ttl: chrono::Duration::hours(1),
+        })
+        .with_refresh_before(Duration::MAX);
+        assert_eq!(provider.get_credential().await?.access_key_id, "id0");
+        assert_eq!(provider.get_credential().await?.access_key_id, "id1");
+        Ok(())
+    }
+}
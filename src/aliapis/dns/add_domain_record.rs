@@ -1,77 +1,35 @@
-use ordermap::OrderMap;
+use api_derive::Api;
 use serde::Deserialize;
 use serde::Serialize;
 
-use crate::Api;
-use crate::RequestHeader;
+use crate::aliapis::dns::{Line, RecordType};
 
-pub struct AddDomainRecord(OrderMap<String, String>);
-
-impl AddDomainRecord {
-    pub fn set_lang(mut self, lang: &str) -> Self{
-        self.0.insert_sorted("Lang".into(), lang.into());
-        self
-    }
-    pub fn set_user_client_ip(mut self, user_client_ip: &str) -> Self{
-        self.0.insert_sorted("UserClientIp".into(), user_client_ip.into());
-        self
-    }
-    pub fn set_domain_name(mut self, domain_name: &str) -> Self{
-        self.0.insert_sorted("DomainName".into(), domain_name.into());
-        self
-    }
-    pub fn set_rr(mut self, rr: &str) -> Self{
-        self.0.insert_sorted("RR".into(), rr.into());
-        self
-    }
-    pub fn set_type(mut self, type_: &str) -> Self{
-        self.0.insert_sorted("Type".into(), type_.to_uppercase());
-        self
-    }
-    pub fn set_value(mut self, value: &str) -> Self{
-        self.0.insert_sorted("Value".into(), value.into());
-        self
-    }
-    pub fn set_ttl(mut self, ttl: &str) -> Self{
-        self.0.insert_sorted("TTL".into(), ttl.into());
-        self
-    }
-    pub fn set_priority(mut self, priority: &str) -> Self{
-        self.0.insert_sorted("Priority".into(), priority.into());
-        self
-    }
-    pub fn set_line(mut self, line: &str) -> Self{
-        self.0.insert_sorted("Line".into(), line.into());
-        self
-    }
-}
-
-impl Api for AddDomainRecord {
-    fn new() -> Self {
-        let mut parameters = OrderMap::new();
-        parameters.insert_sorted("PageSize".into(), 100.to_string());
-        AddDomainRecord(parameters)
-    }
-
-    fn name(&self) -> String {
-        "AddDomainRecord".to_string()
-    }
-
-    fn send(self) -> impl std::future::Future<Output = Result<reqwest::Response, reqwest::Error>> {
-        RequestHeader::new(
-            super::ENDPOINT.to_string(),
-            self.name(),
-            super::VERSION.to_string(),
-            self.0,
-        )
-        .set_method("GET")
-        .sign()
-        .send()
-    }
+#[derive(Debug, Clone, Api)]
+#[api(idempotent = false, default(PageSize = 100))]
+pub struct AddDomainRecord {
+    #[api(name = "Lang")]
+    lang: Option<String>,
+    #[api(name = "UserClientIp")]
+    user_client_ip: Option<String>,
+    #[api(name = "DomainName", required)]
+    domain_name: Option<String>,
+    #[api(name = "RR", required)]
+    rr: Option<String>,
+    ///记录类型，从字符串转换时不区分大小写
+    #[api(name = "Type", required, setter = "set_type")]
+    record_type: Option<RecordType>,
+    #[api(name = "Value", required)]
+    value: Option<String>,
+    #[api(name = "TTL")]
+    ttl: Option<i64>,
+    #[api(name = "Priority")]
+    priority: Option<i64>,
+    #[api(name = "Line")]
+    line: Option<Line>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct AddDomainRecordResponse {
     pub request_id: String,
     pub record_id: String,
@@ -79,20 +37,60 @@ pub struct AddDomainRecordResponse {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Api, AddDomainRecord};
-
-
+    use crate::aliapis::{sign::api_tests, transport::cassette_client};
+    use crate::dns::{AddDomainRecord, RecordType};
+    use crate::{AliError, Api};
 
     #[tokio::test]
-    async fn works() -> anyhow::Result<()> {
-        dotenv::dotenv().ok();
-        let test_api = AddDomainRecord::new().set_domain_name("123clouddrive.cn").set_rr("test").set_type("cname").set_value("www.123pan.com");
-        let response = test_api.send().await?;
+    async fn validate() {
+        let error = AddDomainRecord::new()
+            .set_domain_name("example.com")
+            .set_rr("")
+            .validate()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "AddDomainRecord: missing required parameter RR; missing required parameter Type; missing required parameter Value"
+        );
 
-        //println!("{:?}", response.text().await?);
-
-        assert_eq!(response.status(), 200);
+        // 校验失败时不会签名和发送请求
+        let error = cassette_client("dns/add_domain_record")
+            .execute(AddDomainRecord::new())
+            .await
+            .unwrap_err();
+        let AliError::Validation(error) = error else {
+            panic!("unexpected error {:?}", error);
+        };
+        assert_eq!(error.problems.len(), 4);
+    }
 
-        Ok(())
+    api_tests! {
+        snapshot: AddDomainRecord::new()
+            .set_domain_name("example.com")
+            .set_rr("test")
+            .set_type(RecordType::Cname)
+            .set_value("www.example.com"),
+        canonical_request: "GET\n\
+             /\n\
+             DomainName=example.com&PageSize=100&RR=test&Type=CNAME&Value=www.example.com\n\
+             host:alidns.cn-hangzhou.aliyuncs.com\n\
+             x-acs-action:AddDomainRecord\n\
+             x-acs-content-sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\n\
+             x-acs-date:2024-01-01T00:00:00Z\n\
+             x-acs-signature-nonce:3156853299f313e23d1673dc12e1703d\n\
+             x-acs-version:2015-01-09\n\
+             \n\
+             host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version\n\
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        signature: "03cbd35df8068189420a22d4ad5bf13dd44d55c113b7565f15b8a737a849f20b",
+        cassette: "dns/add_domain_record" => AddDomainRecord::new()
+            .set_domain_name("example.net")
+            .set_rr("cdn")
+            .set_type("cname")
+            .set_value("cdn.example.net.w.kunlunsl.com"),
+        response: |response| {
+            assert_eq!(response.record_id, "802585887865966592");
+            assert_eq!(response.request_id, "E15D7B29-8A4C-5F6E-9B31-5C0A2E8D4F7B");
+        },
     }
 }
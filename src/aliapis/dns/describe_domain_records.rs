@@ -1,108 +1,73 @@
-use ordermap::OrderMap;
+use api_derive::Api;
 use serde::Deserialize;
 use serde::Serialize;
 
-use crate::Api;
-use crate::RequestHeader;
+use crate::aliapis::{
+    dns::{Line, RecordStatus, RecordType},
+    paginate::{next_number, page_count, Page, Paginated},
+    param::SortDirection,
+};
 
-#[derive(Debug, Clone)]
-pub struct DescribeDomainRecords(OrderMap<String, String>);
-
-impl DescribeDomainRecords {
-    pub fn set_lang(mut self, lang: &str) -> Self {
-        self.0.insert_sorted("Lang".into(), lang.into());
-        self
-    }
-    pub fn set_domain_name(mut self, domain_name: &str) -> Self {
-        self.0
-            .insert_sorted("DomainName".into(), domain_name.into());
-        self
-    }
-    pub fn set_key_word(mut self, key_word: &str) -> Self {
-        self.0.insert_sorted("KeyWord".into(), key_word.into());
-        self
-    }
-    pub fn set_rr_key_word(mut self, rr_key_word: &str) -> Self {
-        self.0.insert_sorted("RRKeyWord".into(), rr_key_word.into());
-        self
-    }
-    pub fn set_type_key_word(mut self, type_key_word: &str) -> Self {
-        self.0
-            .insert_sorted("TypeKeyWord".into(), type_key_word.into());
-        self
-    }
-    pub fn set_value_key_word(mut self, value_key_word: &str) -> Self {
-        self.0
-            .insert_sorted("ValueKeyWord".into(), value_key_word.into());
-        self
-    }
-    pub fn set_order_by(mut self, order_by: &str) -> Self {
-        self.0.insert_sorted("OrderBy".into(), order_by.into());
-        self
-    }
-    pub fn set_direction(mut self, direction: &str) -> Self {
-        self.0.insert_sorted("Direction".into(), direction.into());
-        self
-    }
-    pub fn set_search_mode(mut self, search_mode: &str) -> Self {
-        self.0
-            .insert_sorted("SearchMode".into(), search_mode.into());
-        self
-    }
-    pub fn set_group_id(mut self, group_id: &str) -> Self {
-        self.0.insert_sorted("GroupId".into(), group_id.into());
-        self
-    }
-    pub fn set_record_type(mut self, type_: &str) -> Self {
-        self.0.insert_sorted("Type".into(), type_.into());
-        self
-    }
-    pub fn set_line(mut self, line: &str) -> Self {
-        self.0.insert_sorted("Line".into(), line.into());
-        self
-    }
-    pub fn set_status(mut self, status: &str) -> Self {
-        self.0.insert_sorted("Status".into(), status.into());
-        self
-    }
-    pub fn set_page_number(mut self, page_number: i64) -> Self {
-        self.0
-            .insert_sorted("PageNumber".into(), page_number.to_string());
-        self
-    }
-    pub fn set_page_size(mut self, page_size: i64) -> Self {
-        self.0
-            .insert_sorted("PageSize".into(), page_size.to_string());
-        self
-    }
+#[derive(Debug, Clone, Api)]
+#[api(default(PageSize = 500))]
+pub struct DescribeDomainRecords {
+    #[api(name = "Lang")]
+    lang: Option<String>,
+    #[api(name = "DomainName", required)]
+    domain_name: Option<String>,
+    #[api(name = "KeyWord")]
+    key_word: Option<String>,
+    #[api(name = "RRKeyWord")]
+    rr_key_word: Option<String>,
+    #[api(name = "TypeKeyWord")]
+    type_key_word: Option<String>,
+    #[api(name = "ValueKeyWord")]
+    value_key_word: Option<String>,
+    #[api(name = "OrderBy")]
+    order_by: Option<String>,
+    #[api(name = "Direction")]
+    direction: Option<SortDirection>,
+    #[api(name = "SearchMode")]
+    search_mode: Option<String>,
+    #[api(name = "GroupId")]
+    group_id: Option<String>,
+    #[api(name = "Type")]
+    record_type: Option<RecordType>,
+    #[api(name = "Line")]
+    line: Option<Line>,
+    #[api(name = "Status")]
+    status: Option<RecordStatus>,
+    #[api(name = "PageNumber")]
+    page_number: Option<i64>,
+    #[api(name = "PageSize")]
+    page_size: Option<i64>,
 }
 
-impl Api for DescribeDomainRecords {
-    fn new() -> Self {
-        let mut parameters = OrderMap::new();
-        parameters.insert_sorted("PageSize".into(), 500.to_string());
-        DescribeDomainRecords(parameters)
-    }
+impl Paginated for DescribeDomainRecords {
+    type Item = Record;
 
-    fn name(&self) -> String {
-        "DescribeDomainRecords".to_string()
+    fn with_page(self, page: &Page) -> Self {
+        match page {
+            Page::Number(number) => self.set_page_number(*number as i64),
+            Page::Token(_) => self,
+        }
     }
-
-    fn send(self) -> impl std::future::Future<Output = Result<reqwest::Response, reqwest::Error>> {
-        RequestHeader::new(
-            super::ENDPOINT.to_string(),
-            self.name(),
-            super::VERSION.to_string(),
-            self.0,
-        )
-        .set_method("GET")
-        .sign()
-        .send()
+    fn next_page(&self, page: &Page, response: &Self::Response) -> Option<Page> {
+        next_number(page, self.total_pages(response)?)
+    }
+    fn total_pages(&self, response: &Self::Response) -> Option<u64> {
+        Some(page_count(
+            response.total_count as u64,
+            response.page_size as u64,
+        ))
+    }
+    fn into_items(response: Self::Response) -> Vec<Self::Item> {
+        response.domain_records.record
     }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct DescribeDomainRecordsResponse {
     pub total_count: i64,
     pub page_size: i64,
@@ -112,13 +77,13 @@ pub struct DescribeDomainRecordsResponse {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct DomainRecords {
     pub record: Vec<Record>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Record {
     pub status: String,
     #[serde(rename = "RR")]
@@ -139,14 +104,64 @@ pub struct Record {
 
 #[cfg(test)]
 mod tests {
+    use crate::aliapis::sign::api_tests;
     use crate::aliapis::{dns::describe_domain_records::DescribeDomainRecords, sign::Api};
+    use crate::aliapis::{
+        dns::{Line, RecordStatus, RecordType},
+        param::SortDirection,
+    };
 
-    #[tokio::test]
-    async fn works() -> anyhow::Result<()> {
-        let test_api = DescribeDomainRecords::new().set_domain_name("123pan.cn");
-        let response = test_api.send().await?;
-        assert_eq!(response.status(), 200);
+    #[test]
+    fn enum_parameters() {
+        let parameters = DescribeDomainRecords::new()
+            .set_domain_name("example.com")
+            .set_record_type(RecordType::Aaaa)
+            .set_line(Line::Telecom)
+            .set_status(RecordStatus::Disable)
+            .set_direction(SortDirection::Desc)
+            .parameters();
+        assert_eq!(parameters["Type"], "AAAA");
+        assert_eq!(parameters["Line"], "telecom");
+        assert_eq!(parameters["Status"], "Disable");
+        assert_eq!(parameters["Direction"], "DESC");
+
+        // 未列出的取值原样发送
+        let parameters = DescribeDomainRecords::new()
+            .set_record_type("svcb")
+            .set_line(Line::Other("cn_telecom_beijing".to_string()))
+            .parameters();
+        assert_eq!(parameters["Type"], "svcb");
+        assert_eq!(parameters["Line"], "cn_telecom_beijing");
+    }
 
-        Ok(())
+    api_tests! {
+        snapshot: DescribeDomainRecords::new().set_domain_name("example.com"),
+        canonical_request: "GET\n\
+             /\n\
+             DomainName=example.com&PageSize=500\n\
+             host:alidns.cn-hangzhou.aliyuncs.com\n\
+             x-acs-action:DescribeDomainRecords\n\
+             x-acs-content-sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\n\
+             x-acs-date:2024-01-01T00:00:00Z\n\
+             x-acs-signature-nonce:3156853299f313e23d1673dc12e1703d\n\
+             x-acs-version:2015-01-09\n\
+             \n\
+             host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version\n\
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        signature: "b71397d1695a4aabea75f8e6bd1ca83a1d1261740becfc5948a40d65f51154ff",
+        cassette: "dns/describe_domain_records" => DescribeDomainRecords::new().set_domain_name("example.net"),
+        response: |response| {
+            assert_eq!(response.request_id, "2A9F3C6E-4D8B-5A17-8C5F-9E1B3D7A6C40");
+            assert_eq!(response.total_count, 2);
+            let [cdn, apex] = &response.domain_records.record[..] else {
+                panic!("unexpected records {:?}", response.domain_records.record);
+            };
+            assert_eq!((cdn.rr.as_str(), cdn.type_field.as_str()), ("cdn", "CNAME"));
+            assert_eq!(cdn.ttl, 600);
+            assert_eq!(cdn.update_timestamp, Some(1704067200000));
+            assert_eq!(apex.value, "203.0.113.10");
+            assert_eq!(apex.status, "DISABLE");
+            assert_eq!(apex.update_timestamp, None);
+        },
     }
 }
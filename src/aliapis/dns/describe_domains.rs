@@ -1,40 +1,14 @@
-use ordermap::OrderMap;
+use api_derive::Api;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
 
-use crate::aliapis::sign::Api;
-use crate::aliapis::sign::RequestHeader;
-
-#[derive(Debug, Clone)]
-pub struct DescribeDomains(OrderMap<String, String>);
-
-impl Api for DescribeDomains {
-    fn new() -> Self {
-        let mut parameters = OrderMap::new();
-        parameters.insert_sorted("PageSize".into(), 100.to_string());
-        DescribeDomains(parameters)
-    }
-
-    fn name(&self) -> String {
-        "DescribeDomains".to_string()
-    }
-
-    fn send(self) -> impl std::future::Future<Output = Result<reqwest::Response, reqwest::Error>> {
-        RequestHeader::new(
-            super::ENDPOINT.to_string(),
-            self.name(),
-            super::VERSION.to_string(),
-            self.0,
-        )
-        .set_method("POST")
-        .sign()
-        .send()
-    }
-}
+#[derive(Debug, Clone, Api)]
+#[api(method = "POST", body = "form", default(PageSize = 100))]
+pub struct DescribeDomains {}
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct DescribeDomainsResponse {
     pub domains: Domains,
     pub total_count: i64,
@@ -44,13 +18,13 @@ pub struct DescribeDomainsResponse {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Domains {
     pub domain: Vec<Domain>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Domain {
     pub ali_domain: bool,
     pub resource_group_id: String,
@@ -69,27 +43,49 @@ pub struct Domain {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct DnsServers {
     pub dns_server: Vec<String>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Tags {
     pub tag: Vec<Value>,
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::aliapis::sign::api_tests;
     use crate::aliapis::{dns::describe_domains::DescribeDomains, sign::Api};
 
-    #[tokio::test]
-    async fn works() -> anyhow::Result<()> {
-        let test_api = DescribeDomains::new();
-        let response = test_api.send().await?;
-        assert_eq!(response.status(), 403);
-
-        Ok(())
+    api_tests! {
+        snapshot: DescribeDomains::new(),
+        canonical_request: "POST\n\
+             /\n\
+             \n\
+             content-type:application/x-www-form-urlencoded\n\
+             host:alidns.cn-hangzhou.aliyuncs.com\n\
+             x-acs-action:DescribeDomains\n\
+             x-acs-content-sha256:a7329eea930ee1a26401dea84ac0de15c9f1380b66d5f810013d1adedeb789f1\n\
+             x-acs-date:2024-01-01T00:00:00Z\n\
+             x-acs-signature-nonce:3156853299f313e23d1673dc12e1703d\n\
+             x-acs-version:2015-01-09\n\
+             \n\
+             content-type;host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version\n\
+             a7329eea930ee1a26401dea84ac0de15c9f1380b66d5f810013d1adedeb789f1",
+        signature: "0790085aea2fd1373da9cb88e24460973760b18e971a9a39dd7eed6b1e151b3d",
+        cassette: "dns/describe_domains" => DescribeDomains::new(),
+        response: |response| {
+            assert_eq!(response.request_id, "8D6E1A4F-7B3C-5E92-A5D0-6F2C8B1E3A75");
+            assert_eq!(response.total_count, 1);
+            let domain = &response.domains.domain[0];
+            assert_eq!(domain.domain_name, "example.net");
+            assert_eq!(domain.record_count, 2);
+            assert_eq!(
+                domain.dns_servers.dns_server,
+                ["dns1.hichina.com", "dns2.hichina.com"]
+            );
+        },
     }
 }
@@ -1,5 +1,5 @@
 const VERSION: &str = "2015-01-09";
-const ENDPOINT: &str = "alidns.cn-hangzhou.aliyuncs.com";
+const PRODUCT: &str = "Alidns";
 
 pub mod describe_domains;
 pub use describe_domain_records::{DescribeDomainRecords,DescribeDomainRecordsResponse};
@@ -13,5 +13,5 @@ pub use describe_domains::{DescribeDomains,DescribeDomainsResponse};
 pub mod add_domain_record;
 pub use add_domain_record::{AddDomainRecord,AddDomainRecordResponse};
 
-
-
+mod types;
+pub use types::*;
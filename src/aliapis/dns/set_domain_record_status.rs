@@ -1,57 +1,25 @@
-use ordermap::OrderMap;
+use api_derive::Api;
 use serde::Deserialize;
 use serde::Serialize;
 
-use crate::Api;
-use crate::RequestHeader;
-
-pub struct SetDomainRecordStatus(OrderMap<String, String>);
-
-impl SetDomainRecordStatus {
-    pub fn set_lang(mut self, lang: &str) -> Self{
-        self.0.insert_sorted("Lang".into(), lang.into());
-        self
-    }
-    pub fn set_user_client_ip(mut self, user_client_ip: &str) -> Self{
-        self.0.insert_sorted("UserClientIp".into(), user_client_ip.into());
-        self
-    }
-    pub fn set_record_id(mut self, record_id: &str) -> Self{
-        self.0.insert_sorted("RecordId".into(), record_id.into());
-        self
-    }
-    pub fn set_status(mut self, status: &str) -> Self{
-        self.0.insert_sorted("Status".into(), status.into());
-        self
-    }
-}
-
-impl Api for SetDomainRecordStatus {
-    fn new() -> Self {
-        let mut parameters = OrderMap::new();
-        parameters.insert_sorted("PageSize".into(), 100.to_string());
-        SetDomainRecordStatus(parameters)
-    }
-
-    fn name(&self) -> String {
-        "SetDomainRecordStatus".to_string()
-    }
-
-    fn send(self) -> impl std::future::Future<Output = Result<reqwest::Response, reqwest::Error>> {
-        RequestHeader::new(
-            super::ENDPOINT.to_string(),
-            self.name(),
-            super::VERSION.to_string(),
-            self.0,
-        )
-        .set_method("GET")
-        .sign()
-        .send()
-    }
+use crate::aliapis::dns::RecordStatus;
+
+#[derive(Debug, Clone, Api)]
+// 重复设置为同一个状态结果不变，可以安全重试
+#[api(idempotent = true, default(PageSize = 100))]
+pub struct SetDomainRecordStatus {
+    #[api(name = "Lang")]
+    lang: Option<String>,
+    #[api(name = "UserClientIp")]
+    user_client_ip: Option<String>,
+    #[api(name = "RecordId", required)]
+    record_id: Option<String>,
+    #[api(name = "Status", required)]
+    status: Option<RecordStatus>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct SetDomainRecordStatusResponse {
     pub status: String,
     pub request_id: String,
@@ -60,16 +28,34 @@ pub struct SetDomainRecordStatusResponse {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Api, SetDomainRecordStatus};
-
-
-
-    #[tokio::test]
-    async fn works() -> anyhow::Result<()> {
-        let test_api = SetDomainRecordStatus::new().set_record_id("802585887865966592").set_status("Enable");
-        let response = test_api.send().await?;
-        assert_eq!(response.status(), 403);
-
-        Ok(())
+    use crate::aliapis::sign::api_tests;
+    use crate::dns::{RecordStatus, SetDomainRecordStatus};
+    use crate::Api;
+
+    api_tests! {
+        snapshot: SetDomainRecordStatus::new()
+            .set_record_id("802585887865966592")
+            .set_status(RecordStatus::Enable),
+        canonical_request: "GET\n\
+             /\n\
+             PageSize=100&RecordId=802585887865966592&Status=Enable\n\
+             host:alidns.cn-hangzhou.aliyuncs.com\n\
+             x-acs-action:SetDomainRecordStatus\n\
+             x-acs-content-sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\n\
+             x-acs-date:2024-01-01T00:00:00Z\n\
+             x-acs-signature-nonce:3156853299f313e23d1673dc12e1703d\n\
+             x-acs-version:2015-01-09\n\
+             \n\
+             host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version\n\
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        signature: "57ad21cf9a923a718d888839ed3dd53da7b7bbc1f4f1a3699d1c61bd307dae27",
+        cassette: "dns/set_domain_record_status" => SetDomainRecordStatus::new()
+            .set_record_id("802585887865966592")
+            .set_status(RecordStatus::Enable),
+        error: |error| {
+            assert_eq!(error.status(), Some(403));
+            assert_eq!(error.code(), Some("Forbidden.RAM"));
+            assert_eq!(error.request_id(), Some("B3F72D8C-0E5A-5B64-9D1F-4A8E6C2B7D39"));
+        },
     }
 }
@@ -0,0 +1,55 @@
+use crate::aliapis::param::param_enum;
+
+param_enum! {
+    /// 解析记录类型
+    pub enum RecordType {
+        A = "A",
+        Aaaa = "AAAA",
+        Cname = "CNAME",
+        Mx = "MX",
+        Txt = "TXT",
+        Ns = "NS",
+        Srv = "SRV",
+        Caa = "CAA",
+        ///显性 URL 转发
+        RedirectUrl = "REDIRECT_URL",
+        ///隐性 URL 转发
+        ForwardUrl = "FORWARD_URL",
+    }
+}
+
+param_enum! {
+    /// 解析线路
+    pub enum Line {
+        ///默认
+        Default = "default",
+        ///电信
+        Telecom = "telecom",
+        ///联通
+        Unicom = "unicom",
+        ///移动
+        Mobile = "mobile",
+        ///境外
+        Oversea = "oversea",
+        ///教育网
+        Edu = "edu",
+        ///鹏博士
+        Drpeng = "drpeng",
+        ///广电网
+        Btvn = "btvn",
+        ///搜索引擎
+        Search = "search",
+        ///中国地区
+        Internal = "internal",
+    }
+}
+
+param_enum! {
+    /// 解析记录状态
+    pub enum RecordStatus {
+        ///启用
+        Enable = "Enable",
+        ///暂停
+        Disable = "Disable",
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+/// 未指定地域时使用的默认地域
+pub const DEFAULT_REGION: &str = "cn-hangzhou";
+
+/// 访问 endpoint 的网络类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NetworkType {
+    /// 公网
+    #[default]
+    Public,
+    /// VPC 内网，例如 `alidns-vpc.cn-hangzhou.aliyuncs.com`、`cn-hangzhou-intranet.log.aliyuncs.com`
+    Vpc,
+}
+
+/// 根据产品 code、地域和网络类型得到 endpoint
+///
+/// 内置了已实现产品的规则，未知产品按 `{product}.{region}.aliyuncs.com` 拼接。
+/// 用户覆盖优先于内置规则，指定地域的覆盖优先于整个产品的覆盖
+#[derive(Debug, Clone, Default)]
+pub struct EndpointResolver {
+    network: NetworkType,
+    overrides: HashMap<(String, Option<String>), String>,
+}
+
+impl EndpointResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    ///设置网络类型，默认为公网
+    pub fn network(mut self, network: NetworkType) -> Self {
+        self.network = network;
+        self
+    }
+    ///将产品在所有地域的 endpoint 替换为指定的 host，例如国际站或专有云 endpoint
+    pub fn endpoint(mut self, product: &str, host: impl Into<String>) -> Self {
+        self.overrides
+            .insert((product.to_lowercase(), None), host.into());
+        self
+    }
+    ///替换产品在指定地域的 endpoint
+    pub fn region_endpoint(
+        mut self,
+        product: &str,
+        region_id: impl Into<String>,
+        host: impl Into<String>,
+    ) -> Self {
+        self.overrides.insert(
+            (product.to_lowercase(), Some(region_id.into())),
+            host.into(),
+        );
+        self
+    }
+
+    ///解析产品在指定地域的 host，产品 code 不区分大小写
+    pub fn resolve(&self, product: &str, region_id: &str) -> String {
+        let product = product.to_lowercase();
+        if let Some(host) = self
+            .overrides
+            .get(&(product.clone(), Some(region_id.to_string())))
+            .or_else(|| self.overrides.get(&(product.clone(), None)))
+        {
+            return host.clone();
+        }
+
+        let vpc = self.network == NetworkType::Vpc;
+        match product.as_str() {
+            // 中心化部署的产品，中国站和国际站各有一个 endpoint
+            "bssopenapi" if is_mainland(region_id) => "business.aliyuncs.com".to_string(),
+            "bssopenapi" => "business.ap-southeast-1.aliyuncs.com".to_string(),
+            "dysmsapi" if is_mainland(region_id) => "dysmsapi.aliyuncs.com".to_string(),
+            "dysmsapi" => "dysmsapi.ap-southeast-1.aliyuncs.com".to_string(),
+            "sls" if vpc => format!("{}-intranet.log.aliyuncs.com", region_id),
+            "sls" => format!("{}.log.aliyuncs.com", region_id),
+            _ if vpc => format!("{}-vpc.{}.aliyuncs.com", product, region_id),
+            _ => format!("{}.{}.aliyuncs.com", product, region_id),
+        }
+    }
+}
+
+fn is_mainland(region_id: &str) -> bool {
+    region_id.starts_with("cn-") && region_id != "cn-hongkong"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EndpointResolver, NetworkType};
+
+    #[test]
+    fn builtin_rules() {
+        let resolver = EndpointResolver::new();
+        assert_eq!(
+            resolver.resolve("Alidns", "cn-hangzhou"),
+            "alidns.cn-hangzhou.aliyuncs.com"
+        );
+        assert_eq!(
+            resolver.resolve("ARMS", "cn-shanghai"),
+            "arms.cn-shanghai.aliyuncs.com"
+        );
+        assert_eq!(
+            resolver.resolve("BssOpenApi", "cn-beijing"),
+            "business.aliyuncs.com"
+        );
+        assert_eq!(
+            resolver.resolve("BssOpenApi", "ap-southeast-1"),
+            "business.ap-southeast-1.aliyuncs.com"
+        );
+        assert_eq!(
+            resolver.resolve("Dysmsapi", "cn-hongkong"),
+            "dysmsapi.ap-southeast-1.aliyuncs.com"
+        );
+        assert_eq!(
+            resolver.resolve("Sls", "cn-hangzhou"),
+            "cn-hangzhou.log.aliyuncs.com"
+        );
+
+        let resolver = resolver.network(NetworkType::Vpc);
+        assert_eq!(
+            resolver.resolve("Alidns", "cn-hangzhou"),
+            "alidns-vpc.cn-hangzhou.aliyuncs.com"
+        );
+        assert_eq!(
+            resolver.resolve("Sls", "cn-hangzhou"),
+            "cn-hangzhou-intranet.log.aliyuncs.com"
+        );
+    }
+
+    #[test]
+    fn overrides() {
+        let resolver = EndpointResolver::new()
+            .endpoint("arms", "arms.example.com")
+            .region_endpoint("ARMS", "cn-beijing", "arms-beijing.example.com");
+        assert_eq!(resolver.resolve("ARMS", "cn-hangzhou"), "arms.example.com");
+        assert_eq!(
+            resolver.resolve("ARMS", "cn-beijing"),
+            "arms-beijing.example.com"
+        );
+        assert_eq!(
+            resolver.resolve("Alidns", "cn-beijing"),
+            "alidns.cn-beijing.aliyuncs.com"
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use std::fmt;
+
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+
+use crate::aliapis::credential::CredentialError;
+
+/// 调用阿里云 OpenAPI 的错误
+#[derive(Debug)]
+pub enum AliError {
+    /// 网络或 HTTP 协议错误
+    Transport(reqwest::Error),
+    /// 获取凭证失败
+    Credential(CredentialError),
+    /// 签名校验失败，通常是 AK/SK 错误或签名计算有误
+    SignatureMismatch(Box<ServiceError>),
+    /// 触发流控
+    Throttling(Box<ServiceError>),
+    /// 服务端返回的其他错误
+    Service(Box<ServiceError>),
+    /// 返回内容无法解析为响应结构体
+    Decode(serde_json::Error),
+    /// 录制文件读写失败或没有匹配的录制响应
+    Replay(String),
+    /// 发送前校验请求参数失败，请求没有被签名和发送
+    Validation(ValidationError),
+    /// 签名后的请求无法转换为 HTTP 请求，例如 header 值中含有换行，请求没有被发送
+    InvalidRequest(String),
+    /// 同步客户端创建内部的 tokio runtime 失败
+    Runtime(std::io::Error),
+}
+
+impl AliError {
+    /// 根据错误码将服务端错误归类
+    pub fn from_service(error: ServiceError) -> Self {
+        match error.code.as_str() {
+            "SignatureDoesNotMatch" | "IncompleteSignature" | "InvalidSignature" => {
+                AliError::SignatureMismatch(Box::new(error))
+            }
+            code if code.starts_with("Throttling") || error.status == 429 => {
+                AliError::Throttling(Box::new(error))
+            }
+            _ => AliError::Service(Box::new(error)),
+        }
+    }
+    ///服务端返回的错误详情
+    pub fn service_error(&self) -> Option<&ServiceError> {
+        match self {
+            AliError::SignatureMismatch(e) | AliError::Throttling(e) | AliError::Service(e) => {
+                Some(e)
+            }
+            _ => None,
+        }
+    }
+    ///服务端错误码，例如 Throttling.User
+    pub fn code(&self) -> Option<&str> {
+        self.service_error().map(|e| e.code.as_str())
+    }
+    ///HTTP 状态码
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            AliError::Transport(e) => e.status().map(|s| s.as_u16()),
+            _ => self.service_error().map(|e| e.status),
+        }
+    }
+    ///请求 ID，排查问题时提供给阿里云
+    pub fn request_id(&self) -> Option<&str> {
+        self.service_error().map(|e| e.request_id.as_str())
+    }
+}
+
+impl fmt::Display for AliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AliError::Transport(e) => write!(f, "transport error: {}", e),
+            AliError::Credential(e) => write!(f, "{}", e),
+            AliError::SignatureMismatch(e) => write!(f, "signature mismatch: {}", e),
+            AliError::Throttling(e) => write!(f, "throttled: {}", e),
+            AliError::Service(e) => write!(f, "service error: {}", e),
+            AliError::Decode(e) => write!(f, "failed to decode response: {}", e),
+            AliError::Replay(e) => write!(f, "replay error: {}", e),
+            AliError::Validation(e) => write!(f, "invalid request: {}", e),
+            AliError::InvalidRequest(e) => write!(f, "invalid request: {}", e),
+            AliError::Runtime(e) => write!(f, "failed to build tokio runtime: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AliError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AliError::Transport(e) => Some(e),
+            AliError::Credential(e) => Some(e),
+            AliError::Decode(e) => Some(e),
+            AliError::Validation(e) => Some(e),
+            AliError::Runtime(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for AliError {
+    fn from(e: reqwest::Error) -> Self {
+        AliError::Transport(e)
+    }
+}
+
+impl From<CredentialError> for AliError {
+    fn from(e: CredentialError) -> Self {
+        AliError::Credential(e)
+    }
+}
+
+impl From<serde_json::Error> for AliError {
+    fn from(e: serde_json::Error) -> Self {
+        AliError::Decode(e)
+    }
+}
+
+impl From<ValidationError> for AliError {
+    fn from(e: ValidationError) -> Self {
+        AliError::Validation(e)
+    }
+}
+
+/// 请求参数的问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamProblem {
+    /// 缺少必填参数，或参数值为空
+    Missing(String),
+    /// 参数值的格式或取值范围不正确
+    Invalid { name: String, reason: String },
+}
+
+impl fmt::Display for ParamProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamProblem::Missing(name) => write!(f, "missing required parameter {}", name),
+            ParamProblem::Invalid { name, reason } => {
+                write!(f, "invalid parameter {}: {}", name, reason)
+            }
+        }
+    }
+}
+
+/// 请求参数校验失败，包含所有有问题的参数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub action: String,
+    pub problems: Vec<ParamProblem>,
+}
+
+impl ValidationError {
+    pub fn new(action: impl Into<String>) -> Self {
+        Self {
+            action: action.into(),
+            problems: Vec::new(),
+        }
+    }
+    ///记录缺少的必填参数
+    pub fn missing(&mut self, name: &str) {
+        self.problems.push(ParamProblem::Missing(name.to_string()));
+    }
+    ///记录格式错误的参数
+    pub fn invalid(&mut self, name: &str, reason: impl Into<String>) {
+        self.problems.push(ParamProblem::Invalid {
+            name: name.to_string(),
+            reason: reason.into(),
+        });
+    }
+    ///没有问题时返回 `Ok(())`
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.problems.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.action)?;
+        for (i, problem) in self.problems.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// 阿里云返回的错误信息
+///
+/// RPC/ROA 接口返回 `Code`、`Message` 等字段，SLS 返回 `errorCode`、`errorMessage`
+#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ServiceError {
+    #[serde(skip)]
+    pub status: u16,
+    #[serde(alias = "errorCode")]
+    pub code: String,
+    #[serde(alias = "errorMessage")]
+    pub message: String,
+    pub request_id: String,
+    pub recommend: Option<String>,
+    pub host_id: Option<String>,
+}
+
+impl ServiceError {
+    /// 从非 2xx 的返回中解析错误，body 不是 JSON 时保留原文
+    pub fn from_response(status: u16, headers: &HeaderMap, body: &[u8]) -> Self {
+        let mut error =
+            serde_json::from_slice::<ServiceError>(body).unwrap_or_else(|_| ServiceError {
+                code: status.to_string(),
+                message: String::from_utf8_lossy(body).into_owned(),
+                ..Default::default()
+            });
+        error.status = status;
+        if error.request_id.is_empty() {
+            error.request_id = ["x-acs-request-id", "x-log-requestid"]
+                .iter()
+                .find_map(|name| headers.get(*name)?.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+        }
+        error
+    }
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}: {} (RequestId: {})",
+            self.status, self.code, self.message, self.request_id
+        )?;
+        if let Some(recommend) = &self.recommend {
+            write!(f, ", see {}", recommend)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderMap;
+
+    use super::{AliError, ServiceError};
+
+    #[test]
+    fn rpc_error() {
+        let body = br#"{"RequestId":"7A8B2B5C-0E4F-4A1D-9C2B-4B1C6A7E2F01","HostId":"alidns.cn-hangzhou.aliyuncs.com","Code":"SignatureDoesNotMatch","Message":"Specified signature is not matched with our calculation.","Recommend":"https://api.aliyun.com/troubleshoot?q=SignatureDoesNotMatch"}"#;
+        let error =
+            AliError::from_service(ServiceError::from_response(400, &HeaderMap::new(), body));
+
+        let AliError::SignatureMismatch(e) = &error else {
+            panic!("unexpected error {:?}", error);
+        };
+        assert_eq!(e.status, 400);
+        assert_eq!(e.request_id, "7A8B2B5C-0E4F-4A1D-9C2B-4B1C6A7E2F01");
+        assert_eq!(
+            e.host_id.as_deref(),
+            Some("alidns.cn-hangzhou.aliyuncs.com")
+        );
+        assert_eq!(
+            e.recommend.as_deref(),
+            Some("https://api.aliyun.com/troubleshoot?q=SignatureDoesNotMatch")
+        );
+    }
+
+    #[test]
+    fn sls_error() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-log-requestid", "65F1A2B3C4D5E6F7".parse().unwrap());
+        let body = br#"{"errorCode":"ProjectNotExist","errorMessage":"The Project does not exist : demo"}"#;
+        let error = AliError::from_service(ServiceError::from_response(404, &headers, body));
+
+        assert!(matches!(error, AliError::Service(_)));
+        assert_eq!(error.code(), Some("ProjectNotExist"));
+        assert_eq!(error.request_id(), Some("65F1A2B3C4D5E6F7"));
+        assert_eq!(error.status(), Some(404));
+    }
+
+    #[test]
+    fn throttling_and_plain_body() {
+        let body = br#"{"Code":"Throttling.User","Message":"Request was denied due to user flow control.","RequestId":"1"}"#;
+        let error =
+            AliError::from_service(ServiceError::from_response(400, &HeaderMap::new(), body));
+        assert!(matches!(error, AliError::Throttling(_)));
+
+        let error = AliError::from_service(ServiceError::from_response(
+            502,
+            &HeaderMap::new(),
+            b"Bad Gateway",
+        ));
+        assert!(matches!(error, AliError::Service(_)));
+        assert_eq!(error.code(), Some("502"));
+        assert_eq!(error.service_error().unwrap().message, "Bad Gateway");
+    }
+}
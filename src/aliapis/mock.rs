@@ -0,0 +1,898 @@
+//! 进程内的阿里云 OpenAPI mock 服务端
+//!
+//! 按 `sign.rs` 相同的规范化方式重新计算签名并校验，
+//! 校验通过后按 `x-acs-action`（V2 签名为 `Action` 参数）返回预先设置的响应或错误码，
+//! 用于在没有真实账号时端到端地测试签名、错误映射和重试。需要开启 `mock` feature
+//!
+//! 支持 ACS3-HMAC-SHA256、ACS3-HMAC-SM3、ACS3-RSA-SHA256 和 V2 的 HMAC-SHA1 签名。
+//! RSA 签名使用 `MockServer::credential` 注册的私钥对应的公钥校验；
+//! 其他签名方法返回 400 `InvalidSignatureMethod`
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! use libaliopenapi::{billing::QueryAccountBalance, mock::{MockResponse, MockServer}, Api};
+//!
+//! let server = MockServer::start().await?;
+//! server.respond("QueryAccountBalance", MockResponse::json(200, r#"{"Code":"Success"}"#));
+//! let client = server.client_builder().build()?;
+//! let response = client.execute(QueryAccountBalance::new()).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use data_encoding::HEXLOWER_PERMISSIVE;
+use ring::signature::{UnparsedPublicKey, RSA_PKCS1_2048_8192_SHA256};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+use crate::aliapis::{
+    client::AliClientBuilder,
+    credential::StaticProvider,
+    sign::{
+        build_canonical_request, build_rpc_string_to_sign, percent_encode, rpc_v2_signature,
+        rsa_key_pair, SignatureAlgorithm,
+    },
+    transport::{HttpRequest, HttpTransport, ReqwestTransport, TransportFuture},
+};
+
+/// mock 服务端默认接受的 AccessKeyId
+pub const MOCK_ACCESS_KEY_ID: &str = "testid";
+/// mock 服务端默认接受的 AccessKeySecret
+pub const MOCK_ACCESS_KEY_SECRET: &str = "testsecret";
+
+/// mock 服务端返回的响应
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MockResponse {
+    ///返回 JSON body
+    pub fn json(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: body.into(),
+        }
+    }
+    ///返回阿里云格式的错误，例如 `MockResponse::error(400, "Throttling.User", "...")`
+    pub fn error(status: u16, code: &str, message: &str) -> Self {
+        Self::json(
+            status,
+            serde_json::json!({
+                "Code": code,
+                "Message": message,
+                "HostId": "mock",
+            })
+            .to_string(),
+        )
+    }
+    ///添加响应 header
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_lowercase(), value.to_string()));
+        self
+    }
+}
+
+/// mock 服务端收到的请求
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    /// header 名为小写
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// 签名是否校验通过
+    pub signature_valid: bool,
+}
+
+impl ReceivedRequest {
+    ///获取 header，名称不区分大小写
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.headers
+            .iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v.as_str())
+    }
+    ///x-acs-action，V2 签名的请求为查询参数中的 Action
+    pub fn action(&self) -> Option<&str> {
+        self.header("x-acs-action").or_else(|| {
+            self.query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("Action="))
+        })
+    }
+
+    // 查询参数和表单参数，已解码
+    fn params(&self) -> Vec<(String, String)> {
+        let form = match self.header("content-type") {
+            Some(content_type) if content_type.starts_with("application/x-www-form-urlencoded") => {
+                String::from_utf8_lossy(&self.body).into_owned()
+            }
+            _ => String::new(),
+        };
+        [self.query.as_str(), form.as_str()]
+            .iter()
+            .flat_map(|query| query.split('&'))
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+                (percent_decode(k), percent_decode(v))
+            })
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct State {
+    credentials: HashMap<String, String>,
+    responses: HashMap<String, MockResponse>,
+    queued: HashMap<String, VecDeque<MockResponse>>,
+    requests: Vec<ReceivedRequest>,
+}
+
+/// 进程内的 mock 服务端，drop 时停止
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    handle: JoinHandle<()>,
+}
+
+impl MockServer {
+    ///在随机端口启动，接受 `MOCK_ACCESS_KEY_ID`/`MOCK_ACCESS_KEY_SECRET` 签名的请求
+    pub async fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(State::default()));
+        state
+            .lock()
+            .expect("mock state poisoned")
+            .credentials
+            .insert(
+                MOCK_ACCESS_KEY_ID.to_string(),
+                MOCK_ACCESS_KEY_SECRET.to_string(),
+            );
+
+        let server_state = state.clone();
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = server_state.clone();
+                tokio::spawn(async move {
+                    let _ = serve(stream, state).await;
+                });
+            }
+        });
+        Ok(Self {
+            addr,
+            state,
+            handle,
+        })
+    }
+
+    ///监听地址
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+    ///增加一组可以通过校验的 AK/SK，RSA 签名时 secret 为 PEM 格式的私钥，服务端用对应的公钥校验
+    pub fn credential(&self, access_key_id: &str, access_key_secret: &str) -> &Self {
+        self.state()
+            .credentials
+            .insert(access_key_id.to_string(), access_key_secret.to_string());
+        self
+    }
+    ///设置 action 的默认响应
+    pub fn respond(&self, action: &str, response: MockResponse) -> &Self {
+        self.state().responses.insert(action.to_string(), response);
+        self
+    }
+    ///追加 action 的一次性响应，按顺序使用，用完后返回默认响应，用于测试重试
+    pub fn enqueue(&self, action: &str, response: MockResponse) -> &Self {
+        self.state()
+            .queued
+            .entry(action.to_string())
+            .or_default()
+            .push_back(response);
+        self
+    }
+    ///收到的全部请求
+    pub fn requests(&self) -> Vec<ReceivedRequest> {
+        self.state().requests.clone()
+    }
+    ///请求发送到 mock 服务端的传输层，保留原来的 host header
+    pub fn transport(&self) -> MockTransport {
+        MockTransport {
+            addr: self.addr,
+            inner: ReqwestTransport::default(),
+        }
+    }
+    ///使用 mock 传输层和默认 AK/SK 的客户端配置
+    pub fn client_builder(&self) -> AliClientBuilder {
+        AliClientBuilder::default()
+            .transport(self.transport())
+            .credentials(StaticProvider::new(
+                MOCK_ACCESS_KEY_ID,
+                MOCK_ACCESS_KEY_SECRET,
+            ))
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("mock state poisoned")
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// 把 `https://{host}` 的请求改为发送到 mock 服务端的明文 HTTP 地址
+#[derive(Debug, Clone)]
+pub struct MockTransport {
+    addr: SocketAddr,
+    inner: ReqwestTransport,
+}
+
+impl HttpTransport for MockTransport {
+    fn send(&self, mut request: HttpRequest) -> TransportFuture<'_> {
+        let path = request
+            .url
+            .split_once("://")
+            .and_then(|(_, rest)| rest.find('/').map(|i| rest[i..].to_string()))
+            .unwrap_or_else(|| "/".to_string());
+        request.url = format!("http://{}{}", self.addr, path);
+        self.inner.send(request)
+    }
+}
+
+// 处理一个连接上的请求，支持 keep-alive
+async fn serve(stream: TcpStream, state: Arc<Mutex<State>>) -> io::Result<()> {
+    let mut stream = BufReader::new(stream);
+    loop {
+        let mut request_line = String::new();
+        if stream.read_line(&mut request_line).await? == 0 {
+            return Ok(());
+        }
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let target = parts.next().unwrap_or("/").to_string();
+
+        let mut headers = Vec::new();
+        loop {
+            let mut line = String::new();
+            stream.read_line(&mut line).await?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+            }
+        }
+        let length = headers
+            .iter()
+            .find(|(k, _)| k == "content-length")
+            .and_then(|(_, v)| v.parse().ok())
+            .unwrap_or(0);
+        let mut body = vec![0; length];
+        stream.read_exact(&mut body).await?;
+
+        let (path, query) = match target.split_once('?') {
+            Some((path, query)) => (path.to_string(), query.to_string()),
+            None => (target, String::new()),
+        };
+        let mut request = ReceivedRequest {
+            method,
+            path,
+            query,
+            headers,
+            body,
+            signature_valid: false,
+        };
+
+        let response = {
+            let mut state = state.lock().expect("mock state poisoned");
+            let response = handle(&mut state, &mut request);
+            state.requests.push(request);
+            response
+        };
+
+        let request_id = format!(
+            "MOCK-{:04}",
+            state.lock().expect("mock state poisoned").requests.len()
+        );
+        let body = match serde_json::from_str::<serde_json::Value>(&response.body) {
+            Ok(serde_json::Value::Object(mut object)) if !object.contains_key("RequestId") => {
+                object.insert("RequestId".to_string(), request_id.clone().into());
+                serde_json::Value::Object(object).to_string()
+            }
+            _ => response.body,
+        };
+        let mut head = format!("HTTP/1.1 {} MOCK\r\n", response.status);
+        for (name, value) in &response.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str(&format!(
+            "x-acs-request-id: {}\r\ncontent-length: {}\r\n\r\n",
+            request_id,
+            body.len()
+        ));
+        let stream = stream.get_mut();
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(body.as_bytes()).await?;
+        stream.flush().await?;
+    }
+}
+
+// 校验签名后选择响应
+fn handle(state: &mut State, request: &mut ReceivedRequest) -> MockResponse {
+    if let Err(response) = verify(state, request) {
+        return response;
+    }
+    request.signature_valid = true;
+
+    let action = request.action().unwrap_or_default().to_string();
+    if let Some(response) = state.queued.get_mut(&action).and_then(VecDeque::pop_front) {
+        return response;
+    }
+    state.responses.get(&action).cloned().unwrap_or_else(|| {
+        MockResponse::error(
+            404,
+            "InvalidAction.NotFound",
+            &format!(
+                "Specified api is not found, please check your url and method: {}",
+                action
+            ),
+        )
+    })
+}
+
+// 按阿里云的规则重新计算签名
+fn verify(state: &State, request: &ReceivedRequest) -> Result<(), MockResponse> {
+    if request.header("authorization").is_none() {
+        let params = request.params();
+        if params.iter().any(|(k, _)| k == "Signature") {
+            return verify_rpc_v2(state, request, &params);
+        }
+    }
+    let authorization = request.header("authorization").unwrap_or_default();
+    let Some((algorithm_name, fields)) = authorization.split_once(' ') else {
+        return Err(MockResponse::error(
+            400,
+            "MissingAuthorization",
+            "Authorization is mandatory for this action.",
+        ));
+    };
+    let algorithm = match algorithm_name {
+        "ACS3-HMAC-SHA256" => SignatureAlgorithm::HmacSha256,
+        "ACS3-HMAC-SM3" => SignatureAlgorithm::HmacSm3,
+        "ACS3-RSA-SHA256" => SignatureAlgorithm::RsaSha256,
+        _ => {
+            return Err(MockResponse::error(
+                400,
+                "InvalidSignatureMethod",
+                &format!("The signature method {} is not supported.", algorithm_name),
+            ))
+        }
+    };
+    let field = |name: &str| {
+        fields
+            .split(',')
+            .find_map(|field| field.trim().strip_prefix(name)?.strip_prefix('='))
+            .unwrap_or_default()
+    };
+    let (access_key_id, signed_headers, signature) = (
+        field("Credential"),
+        field("SignedHeaders"),
+        field("Signature"),
+    );
+
+    let secret = secret(state, access_key_id)?;
+
+    let hashed_payload = algorithm.hash(&request.body);
+    if request.header(algorithm.content_header()) != Some(hashed_payload.as_str()) {
+        return Err(MockResponse::error(
+            400,
+            "SignatureDoesNotMatch",
+            "The payload hash does not match the request body.",
+        ));
+    }
+
+    let mut names: Vec<&str> = signed_headers.split(';').collect();
+    names.sort();
+    let headers = names
+        .iter()
+        .map(|name| (*name, request.header(name).unwrap_or_default()));
+    let (canonical_request, expected_signed_headers) = build_canonical_request(
+        &request.method,
+        &request.path,
+        &canonical_query(&request.query),
+        headers,
+        &hashed_payload,
+    );
+    let string_to_sign = format!(
+        "{}\n{}",
+        algorithm.name(),
+        algorithm.hash(canonical_request.as_bytes())
+    );
+    let matched = match algorithm {
+        SignatureAlgorithm::RsaSha256 => verify_rsa(secret, &string_to_sign, signature),
+        _ => algorithm
+            .sign(secret, &string_to_sign)
+            .is_ok_and(|expected| expected == signature),
+    };
+
+    if expected_signed_headers != signed_headers || !matched {
+        return Err(MockResponse::error(
+            400,
+            "SignatureDoesNotMatch",
+            &format!(
+                "Specified signature is not matched with our calculation. server string to sign is:{}",
+                string_to_sign
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn secret<'a>(state: &'a State, access_key_id: &str) -> Result<&'a str, MockResponse> {
+    state
+        .credentials
+        .get(access_key_id)
+        .map(String::as_str)
+        .ok_or_else(|| {
+            MockResponse::error(
+                404,
+                "InvalidAccessKeyId.NotFound",
+                "Specified access key is not found.",
+            )
+        })
+}
+
+// 用注册的私钥对应的公钥校验十六进制编码的签名，注册的不是 RSA 私钥时校验失败
+fn verify_rsa(private_key: &str, string_to_sign: &str, signature: &str) -> bool {
+    let (Ok(key_pair), Ok(signature)) = (
+        rsa_key_pair(private_key),
+        HEXLOWER_PERMISSIVE.decode(signature.as_bytes()),
+    ) else {
+        return false;
+    };
+    UnparsedPublicKey::new(&RSA_PKCS1_2048_8192_SHA256, key_pair.public().as_ref())
+        .verify(string_to_sign.as_bytes(), &signature)
+        .is_ok()
+}
+
+// V2 签名：对查询参数和表单参数重新计算 HMAC-SHA1
+fn verify_rpc_v2(
+    state: &State,
+    request: &ReceivedRequest,
+    params: &[(String, String)],
+) -> Result<(), MockResponse> {
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+            .unwrap_or_default()
+    };
+    if param("SignatureMethod") != "HMAC-SHA1" {
+        return Err(MockResponse::error(
+            400,
+            "InvalidSignatureMethod",
+            &format!(
+                "The signature method {} is not supported.",
+                param("SignatureMethod")
+            ),
+        ));
+    }
+    let secret = secret(state, param("AccessKeyId"))?;
+
+    let string_to_sign = build_rpc_string_to_sign(
+        &request.method,
+        params.iter().map(|(k, v)| (k.as_str(), v.as_str())),
+    );
+    if rpc_v2_signature(secret, &string_to_sign) != param("Signature") {
+        return Err(MockResponse::error(
+            400,
+            "SignatureDoesNotMatch",
+            &format!(
+                "Specified signature is not matched with our calculation. server string to sign is:{}",
+                string_to_sign
+            ),
+        ));
+    }
+    Ok(())
+}
+
+// 服务端收到的查询字符串先解码再按 RFC 3986 编码、排序
+fn canonical_query(query: &str) -> String {
+    let mut params: Vec<(String, String)> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (
+                percent_encode(&percent_decode(k)),
+                percent_encode(&percent_decode(v)),
+            )
+        })
+        .collect();
+    params.sort();
+    params
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match (bytes[i], bytes.get(i + 1..i + 3)) {
+            (b'%', Some(hex)) => match u8::from_str_radix(&String::from_utf8_lossy(hex), 16) {
+                Ok(byte) => {
+                    decoded.push(byte);
+                    i += 3;
+                }
+                Err(_) => {
+                    decoded.push(b'%');
+                    i += 1;
+                }
+            },
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// 测试使用账单、DNS 和短信的接口，ARMS 和 SLS 的测试另外需要对应的 feature
+#[cfg(all(test, feature = "billing", feature = "dns", feature = "sms"))]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::NaiveDate;
+
+    use super::{MockResponse, MockServer};
+    use crate::aliapis::{
+        billing::QueryAccountBalance,
+        credential::StaticProvider,
+        dns::{AddDomainRecord, RecordType},
+        error::AliError,
+        retry::RetryPolicy,
+        sign::{Api, SignatureAlgorithm},
+        sms::QuerySendStatistics,
+    };
+
+    const BALANCE: &str = r#"{"Code":"Success","Message":"Successful!","Success":true,"Data":{"AvailableAmount":"1.00","AvailableCashAmount":"1.00","CreditAmount":"0.00","Currency":"CNY","MybankCreditAmount":"0.00","QuotaLimit":"0.00"}}"#;
+
+    #[tokio::test]
+    async fn verifies_signature() -> anyhow::Result<()> {
+        let server = MockServer::start().await?;
+        server.respond("QueryAccountBalance", MockResponse::json(200, BALANCE));
+
+        let client = server.client_builder().build()?;
+        let response = client.execute_typed(QueryAccountBalance::new()).await?;
+        assert_eq!(response.data.currency, "CNY");
+        assert_eq!(response.request_id, "MOCK-0001");
+
+        // 表单 body 和 SM3 签名
+        server.respond("QuerySendStatistics", MockResponse::json(200, "{}"));
+        let client = server
+            .client_builder()
+            .signature_algorithm(SignatureAlgorithm::HmacSm3)
+            .build()?;
+        let api = QuerySendStatistics::new()
+            .set_is_globe(1)
+            .set_start_date(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+            .set_end_date(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!(client.execute(api).await?.status(), 200);
+
+        let requests = server.requests();
+        assert!(requests.iter().all(|r| r.signature_valid));
+        assert_eq!(requests[1].header("host"), Some("dysmsapi.aliyuncs.com"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn verifies_rsa_signature() -> anyhow::Result<()> {
+        let private_key = include_str!("../../fixtures/rsa_private_key.pem");
+        let server = MockServer::start().await?;
+        server
+            .credential("rsaid", private_key)
+            .respond("QueryAccountBalance", MockResponse::json(200, BALANCE));
+
+        let client = server
+            .client_builder()
+            .credentials(StaticProvider::new("rsaid", private_key))
+            .signature_algorithm(SignatureAlgorithm::RsaSha256)
+            .build()?;
+        client.execute_typed(QueryAccountBalance::new()).await?;
+        assert!(server.requests()[0].signature_valid);
+
+        // 注册的 secret 不是这个私钥时校验失败
+        let client = server
+            .client_builder()
+            .signature_algorithm(SignatureAlgorithm::RsaSha256)
+            .credentials(StaticProvider::new("testid", private_key))
+            .build()?;
+        let error = client
+            .execute(QueryAccountBalance::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AliError::SignatureMismatch(_)), "{}", error);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn verifies_rpc_v2_signature() -> anyhow::Result<()> {
+        #[derive(Debug, Clone, api_derive::Api)]
+        #[api(
+            action = "DescribeRegions",
+            version = "2014-05-26",
+            endpoint = "ecs.aliyuncs.com",
+            response = "serde_json::Value",
+            signature = "v2"
+        )]
+        struct Regions {
+            #[api(name = "RegionId")]
+            region_id: Option<String>,
+        }
+
+        let server = MockServer::start().await?;
+        server.respond("DescribeRegions", MockResponse::json(200, "{}"));
+        let client = server
+            .client_builder()
+            .credentials(StaticProvider::new("testid", "testsecret").with_security_token("a+b/c="))
+            .build()?;
+        client
+            .execute(Regions::new().set_region_id("cn-hangzhou"))
+            .await?;
+        let request = &server.requests()[0];
+        assert!(request.signature_valid);
+        assert_eq!(request.action(), Some("DescribeRegions"));
+        assert_eq!(request.header("authorization"), None);
+
+        let client = server
+            .client_builder()
+            .credentials(StaticProvider::new("testid", "wrongsecret"))
+            .build()?;
+        let error = client.execute(Regions::new()).await.unwrap_err();
+        assert!(matches!(error, AliError::SignatureMismatch(_)), "{}", error);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rejects_unsupported_algorithm() -> anyhow::Result<()> {
+        let server = MockServer::start().await?;
+        let client = reqwest::Client::new();
+        let response = client
+            .get(format!("http://{}/", server.addr()))
+            .header("authorization", "ACS3-HMAC-SHA512 Credential=testid")
+            .send()
+            .await?;
+        assert_eq!(response.status(), 400);
+        assert!(response.text().await?.contains("InvalidSignatureMethod"));
+
+        let response = client
+            .get(format!(
+                "http://{}/?Action=DescribeRegions&AccessKeyId=testid&SignatureMethod=HMAC-SHA256&Signature=abc",
+                server.addr()
+            ))
+            .send()
+            .await?;
+        assert_eq!(response.status(), 400);
+        assert!(response.text().await?.contains("InvalidSignatureMethod"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_signature() -> anyhow::Result<()> {
+        let server = MockServer::start().await?;
+        server.respond("QueryAccountBalance", MockResponse::json(200, BALANCE));
+
+        let client = server
+            .client_builder()
+            .credentials(StaticProvider::new("testid", "wrongsecret"))
+            .build()?;
+        let error = client
+            .execute(QueryAccountBalance::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AliError::SignatureMismatch(_)), "{}", error);
+        assert_eq!(error.request_id(), Some("MOCK-0001"));
+
+        let client = server
+            .client_builder()
+            .credentials(StaticProvider::new("unknown", "testsecret"))
+            .build()?;
+        let error = client
+            .execute(QueryAccountBalance::new())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Some("InvalidAccessKeyId.NotFound"));
+        assert!(!server.requests().iter().any(|r| r.signature_valid));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn retries_with_fresh_signature() -> anyhow::Result<()> {
+        let server = MockServer::start().await?;
+        let throttled = MockResponse::error(
+            400,
+            "Throttling.User",
+            "Request was denied due to user flow control.",
+        );
+        server
+            .enqueue("QueryAccountBalance", throttled.clone())
+            .enqueue("QueryAccountBalance", throttled.clone())
+            .respond("QueryAccountBalance", MockResponse::json(200, BALANCE));
+
+        let client = server
+            .client_builder()
+            .retry_policy(RetryPolicy::new().base_delay(Duration::from_millis(1)))
+            .build()?;
+        client.execute_typed(QueryAccountBalance::new()).await?;
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().all(|r| r.signature_valid));
+        assert_ne!(
+            requests[0].header("x-acs-signature-nonce"),
+            requests[1].header("x-acs-signature-nonce")
+        );
+
+        // 非幂等请求不重试
+        server.enqueue("AddDomainRecord", throttled);
+        let api = AddDomainRecord::new()
+            .set_domain_name("example.com")
+            .set_rr("www")
+            .set_type(RecordType::A)
+            .set_value("127.0.0.1");
+        let error = client.execute(api).await.unwrap_err();
+        assert!(matches!(error, AliError::Throttling(_)));
+        assert_eq!(server.requests().len(), 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn error_codes() -> anyhow::Result<()> {
+        let server = MockServer::start().await?;
+        server.respond(
+            "QueryAccountBalance",
+            MockResponse::error(400, "InvalidParameter", "The parameter is invalid."),
+        );
+        let client = server
+            .client_builder()
+            .retry_policy(RetryPolicy::none())
+            .build()?;
+
+        let error = client
+            .execute(QueryAccountBalance::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AliError::Service(_)));
+        assert_eq!(error.code(), Some("InvalidParameter"));
+        assert_eq!(error.status(), Some(400));
+
+        let api = QuerySendStatistics::new()
+            .set_is_globe(1)
+            .set_start_date(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+            .set_end_date(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        let error = client.execute(api).await.unwrap_err();
+        assert_eq!(error.code(), Some("InvalidAction.NotFound"));
+        assert_eq!(error.status(), Some(404));
+        Ok(())
+    }
+
+    #[cfg(feature = "arms")]
+    #[tokio::test]
+    async fn arms_region_and_millis() -> anyhow::Result<()> {
+        use chrono::{TimeZone, Utc};
+
+        use crate::aliapis::arms::{ListSyntheticDetail, SyntheticDetail};
+
+        let server = MockServer::start().await?;
+        server.respond(
+            "ListSyntheticDetail",
+            MockResponse::json(
+                200,
+                r#"{"Code":200,"Data":{"Page":1,"PageSize":300,"Total":1,"Items":[{"__time__":1704003660,"taskName":"example-download","timestamp":"1704003660000"}]}}"#,
+            ),
+        );
+        let client = server.client_builder().region_id("cn-shanghai").build()?;
+        let api = ListSyntheticDetail::new()
+            .set_synthetic_type(2)
+            .set_detail(SyntheticDetail::DownloadList)
+            .set_start_time(Utc.timestamp_millis_opt(1703980800000).unwrap())
+            .set_end_time(Utc.timestamp_millis_opt(1704067200000).unwrap())
+            .set_filters("f124d885bcbc4d78a7fcb3a020b6ad66");
+        let response = client.execute_typed(api).await?;
+        assert_eq!(response.data.items[0].timestamp, "1704003660000");
+
+        let request = &server.requests()[0];
+        assert!(request.signature_valid);
+        assert_eq!(
+            request.header("host"),
+            Some("arms.cn-shanghai.aliyuncs.com")
+        );
+        let params = request.params();
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(param("RegionId"), Some("cn-shanghai"));
+        assert_eq!(param("StartTime"), Some("1703980800000"));
+        assert_eq!(param("EndTime"), Some("1704067200000"));
+        assert_eq!(
+            param("Filters"),
+            Some(r#"{"taskId": "f124d885bcbc4d78a7fcb3a020b6ad66"}"#)
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "sls")]
+    #[tokio::test]
+    async fn sls_project_host_and_json_body() -> anyhow::Result<()> {
+        use chrono::{TimeZone, Utc};
+
+        use crate::aliapis::sls::GetLogsV2;
+
+        let server = MockServer::start().await?;
+        server.respond(
+            "GetLogsV2",
+            MockResponse::json(
+                200,
+                r#"{"meta":{"count":1,"progress":"Complete"},"data":[{"__time__":"1704067230","status":"200"}]}"#,
+            ),
+        );
+        let client = server.client_builder().region_id("cn-shanghai").build()?;
+        let api = GetLogsV2::new()
+            .set_project("demo")
+            .set_logstore("gateway")
+            .set_from(Utc.timestamp_opt(1704067200, 0).unwrap())
+            .set_to(Utc.timestamp_opt(1704070800, 0).unwrap())
+            .set_query("status: 200");
+        let response = client.execute_typed(api).await?;
+        assert_eq!(response.meta.count, 1);
+        assert_eq!(response.data[0].status, "200");
+
+        let request = &server.requests()[0];
+        assert!(request.signature_valid);
+        assert_eq!(
+            request.header("host"),
+            Some("demo.cn-shanghai.log.aliyuncs.com")
+        );
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/logstores/gateway/logs");
+        assert_eq!(request.query, "");
+        assert_eq!(request.header("content-type"), Some("application/json"));
+        assert_eq!(request.header("accept-encoding"), Some("gzip"));
+        let body: serde_json::Value = serde_json::from_slice(&request.body)?;
+        assert_eq!(
+            body,
+            serde_json::json!({"from": "1704067200", "to": "1704070800", "query": "status: 200"})
+        );
+        Ok(())
+    }
+}
@@ -1,13 +1,40 @@
 pub mod sign;
+pub mod credential;
+pub mod client;
+pub mod endpoint;
+pub mod error;
+pub mod retry;
+pub mod transport;
+pub mod paginate;
+pub mod param;
+pub mod prepare;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod sm3;
+#[cfg(feature = "arms")]
 pub mod arms;
+#[cfg(feature = "billing")]
 pub mod billing;
+#[cfg(feature = "sms")]
 pub mod sms;
+#[cfg(feature = "dns")]
 pub mod dns;
-mod sls;
+#[cfg(feature = "sls")]
+pub mod sls;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+#[cfg(test)]
+mod codegen_snapshots;
 
 pub use sign::*;
-pub use arms::*;
-pub use billing::*;
-pub use sms::*;
-pub use dns::*;
-pub use sls::*;
\ No newline at end of file
+pub use credential::*;
+pub use client::*;
+pub use endpoint::*;
+pub use error::*;
+pub use retry::*;
+pub use transport::*;
+pub use param::*;
+pub use paginate::*;
+pub use prepare::*;
+#[cfg(feature = "blocking")]
+pub use blocking::*;
\ No newline at end of file
@@ -0,0 +1,477 @@
+use futures::{
+    future,
+    stream::{self, BoxStream, StreamExt},
+};
+
+use crate::aliapis::{
+    client::{default_client, AliClient},
+    error::AliError,
+    sign::Api,
+};
+
+/// 分页接口请求的页
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Page {
+    /// 页码，从 1 开始
+    Number(u64),
+    /// 上一页返回的 NextToken，第一页为空
+    Token(String),
+}
+
+/// 分页接口，统一 PageNumber、PageNum、Page、NextToken、offset/line 等不同的分页方式
+pub trait Paginated: Api<Response: Send> + Clone + Send + Sync + 'static {
+    /// 每页中的条目
+    type Item: Send;
+
+    /// 第一页，默认为 `Page::Number(1)`
+    fn first_page() -> Page {
+        Page::Number(1)
+    }
+    /// 设置要请求的页
+    fn with_page(self, page: &Page) -> Self;
+    /// 当前页之后的页，`None` 表示已经是最后一页
+    fn next_page(&self, page: &Page, response: &Self::Response) -> Option<Page>;
+    /// 根据第一页的返回计算总页数，已知总页数时剩余的页可以并发请求
+    fn total_pages(&self, _response: &Self::Response) -> Option<u64> {
+        None
+    }
+    /// 取出本页的条目
+    fn into_items(response: Self::Response) -> Vec<Self::Item>;
+
+    /// 使用默认客户端分页请求
+    fn paginate(self) -> Paginator<Self> {
+        default_client().paginate(self)
+    }
+}
+
+// 按总数和每页数量计算总页数
+#[cfg(any(feature = "dns", feature = "billing", feature = "arms"))]
+pub(crate) fn page_count(total: u64, page_size: u64) -> u64 {
+    if page_size == 0 {
+        return 0;
+    }
+    total.div_ceil(page_size)
+}
+
+// 按页码翻页的接口在未超过总页数时请求下一页
+#[cfg(any(feature = "dns", feature = "billing", feature = "arms"))]
+pub(crate) fn next_number(page: &Page, total_pages: u64) -> Option<Page> {
+    match page {
+        Page::Number(number) if *number < total_pages => Some(Page::Number(number + 1)),
+        _ => None,
+    }
+}
+
+/// 按页请求分页接口，返回页或条目的 `Stream`
+///
+/// 默认逐页请求。设置 `concurrency` 后，第一页返回了总数的接口会并发请求剩余的页，
+/// 结果仍按页码顺序返回；只能按 NextToken 翻页的接口始终逐页请求
+pub struct Paginator<A> {
+    client: AliClient,
+    api: A,
+    max_pages: Option<u64>,
+    concurrency: usize,
+}
+
+impl<A: Paginated> Paginator<A> {
+    pub fn new(client: AliClient, api: A) -> Self {
+        Self {
+            client,
+            api,
+            max_pages: None,
+            concurrency: 1,
+        }
+    }
+    ///最多请求的页数
+    pub fn max_pages(mut self, max_pages: u64) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+    ///同时请求的页数，默认为 1
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    ///每页的返回
+    pub fn pages(self) -> BoxStream<'static, Result<A::Response, AliError>> {
+        let Paginator {
+            client,
+            api,
+            max_pages,
+            concurrency,
+        } = self;
+        let limit = max_pages.unwrap_or(u64::MAX);
+        if limit == 0 {
+            return stream::empty().boxed();
+        }
+
+        let first = A::first_page();
+        let head = {
+            let (client, api) = (client.clone(), api.clone().with_page(&first));
+            async move { client.execute_typed(api).await }
+        };
+        stream::once(head)
+            .flat_map(move |result| {
+                let response = match result {
+                    Ok(response) => response,
+                    Err(e) => return stream::once(future::ready(Err(e))).boxed(),
+                };
+                let total = api.total_pages(&response);
+                let rest = match (api.next_page(&first, &response), total) {
+                    _ if limit == 1 => stream::empty().boxed(),
+                    (Some(_), Some(total)) if concurrency > 1 => {
+                        let (client, api) = (client.clone(), api.clone());
+                        stream::iter(2..=total.min(limit))
+                            .map(move |number| {
+                                let client = client.clone();
+                                let api = api.clone().with_page(&Page::Number(number));
+                                async move { client.execute_typed(api).await }
+                            })
+                            .buffered(concurrency)
+                            .boxed()
+                    }
+                    (Some(next), _) => sequential(client.clone(), api.clone(), next, limit - 1),
+                    (None, _) => stream::empty().boxed(),
+                };
+                stream::once(future::ready(Ok(response)))
+                    .chain(rest)
+                    .boxed()
+            })
+            .boxed()
+    }
+
+    ///所有页中的条目，请求失败时返回错误后结束
+    pub fn items(self) -> BoxStream<'static, Result<A::Item, AliError>> {
+        self.pages()
+            .scan(false, |failed, result| {
+                if *failed {
+                    return future::ready(None);
+                }
+                let items = match result {
+                    Ok(response) => A::into_items(response).into_iter().map(Ok).collect(),
+                    Err(e) => {
+                        *failed = true;
+                        vec![Err(e)]
+                    }
+                };
+                future::ready(Some(stream::iter(items)))
+            })
+            .flatten()
+            .boxed()
+    }
+}
+
+// 逐页请求，直到没有下一页、达到页数限制或请求失败
+fn sequential<A: Paginated>(
+    client: AliClient,
+    api: A,
+    page: Page,
+    remaining: u64,
+) -> BoxStream<'static, Result<A::Response, AliError>> {
+    stream::unfold(Some((page, remaining)), move |state| {
+        let (client, api) = (client.clone(), api.clone());
+        async move {
+            let (page, remaining) = state.filter(|(_, remaining)| *remaining > 0)?;
+            match client.execute_typed(api.clone().with_page(&page)).await {
+                Ok(response) => {
+                    let next = api
+                        .next_page(&page, &response)
+                        .map(|next| (next, remaining - 1));
+                    Some((Ok(response), next))
+                }
+                Err(e) => Some((Err(e), None)),
+            }
+        }
+    })
+    .boxed()
+}
+
+impl AliClient {
+    ///使用当前客户端分页请求
+    pub fn paginate<A: Paginated>(&self, api: A) -> Paginator<A> {
+        Paginator::new(self.clone(), api)
+    }
+}
+
+// 测试使用 ARMS、账单、DNS 和 SLS 的分页接口
+#[cfg(all(
+    test,
+    feature = "arms",
+    feature = "billing",
+    feature = "dns",
+    feature = "sls"
+))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use chrono::{TimeZone, Utc};
+    use futures::TryStreamExt;
+    use reqwest::header::HeaderMap;
+
+    use super::{page_count, Paginated};
+    use crate::aliapis::{
+        arms::{
+            list_synthetic_detail::{self, ListSyntheticDetailResponse},
+            ListSyntheticDetail, SyntheticDetail,
+        },
+        billing::{
+            describe_instance_bill::{Data, Item},
+            DescribeInstanceBill, DescribeInstanceBillResponse,
+        },
+        client::AliClient,
+        credential::StaticProvider,
+        dns::{
+            describe_domain_records::{DomainRecords, Record},
+            DescribeDomainRecords, DescribeDomainRecordsResponse,
+        },
+        param::YearMonth,
+        retry::RetryPolicy,
+        sign::Api,
+        sls::{get_logs_v2::Daum, GetLogsV2, GetLogsV2Response},
+        transport::{HttpRequest, HttpResponse, HttpTransport, TransportFuture},
+    };
+
+    // 按请求中的分页参数生成返回，记录请求的 URL 和 body
+    #[derive(Clone, Default)]
+    struct Pages(Arc<Mutex<Vec<String>>>);
+
+    impl Pages {
+        fn client(&self) -> AliClient {
+            AliClient::builder()
+                .retry_policy(RetryPolicy::none())
+                .credentials(StaticProvider::new("testid", "testsecret"))
+                .transport(self.clone())
+                .build()
+                .unwrap()
+        }
+        fn requests(&self) -> Vec<String> {
+            self.0.lock().unwrap().clone()
+        }
+    }
+
+    fn param(url: &str, name: &str) -> Option<String> {
+        url.split_once('?')?
+            .1
+            .split('&')
+            .find_map(|pair| Some(pair.strip_prefix(name)?.strip_prefix('=')?.to_string()))
+    }
+
+    impl HttpTransport for Pages {
+        fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+            let body = String::from_utf8_lossy(&request.body).into_owned();
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", request.url, body));
+            let body = if request.url.contains("arms.") {
+                // 共 3 条拨测结果，每页 2 条
+                let page: i64 = param(&request.url, "Page").unwrap().parse().unwrap();
+                let page_size: i64 = param(&request.url, "PageSize").unwrap().parse().unwrap();
+                let item = (page - 1) * page_size..(page * page_size).min(3);
+                serde_json::to_value(ListSyntheticDetailResponse {
+                    code: 200,
+                    data: list_synthetic_detail::Data {
+                        page,
+                        page_size,
+                        total: 3,
+                        items: item
+                            .map(|i| list_synthetic_detail::Item {
+                                data_id: format!("data-{}", i),
+                                task_name: "example-download".to_string(),
+                                time: 1704003600 + i * 60,
+                                ..Default::default()
+                            })
+                            .collect(),
+                    },
+                    ..Default::default()
+                })
+            } else if let Some(number) = param(&request.url, "PageNumber") {
+                // 共 5 条记录，每页 2 条
+                let number: i64 = number.parse().unwrap();
+                let record = (number - 1) * 2 + 1..=(number * 2).min(5);
+                serde_json::to_value(DescribeDomainRecordsResponse {
+                    total_count: 5,
+                    page_size: 2,
+                    page_number: number,
+                    domain_records: DomainRecords {
+                        record: record
+                            .map(|i| Record {
+                                record_id: i.to_string(),
+                                ..Default::default()
+                            })
+                            .collect(),
+                    },
+                    ..Default::default()
+                })
+            } else if request.url.contains("log.aliyuncs.com") {
+                // 共 3 条日志
+                let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+                let offset: usize = body["offset"].as_str().unwrap().parse().unwrap();
+                let line: usize = body["line"].as_str().unwrap().parse().unwrap();
+                serde_json::to_value(GetLogsV2Response {
+                    data: (offset..(offset + line).min(3))
+                        .map(|i| Daum {
+                            request_uri: format!("/{}", i),
+                            ..Default::default()
+                        })
+                        .collect(),
+                    ..Default::default()
+                })
+            } else {
+                let token = param(&request.url, "NextToken").unwrap_or_default();
+                let next_token = match token.as_str() {
+                    "" => "a",
+                    "a" => "b",
+                    _ => "",
+                };
+                serde_json::to_value(DescribeInstanceBillResponse {
+                    data: Data {
+                        next_token: next_token.to_string(),
+                        items: vec![Item {
+                            instance_id: token,
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+            }
+            .unwrap();
+            Box::pin(async move {
+                Ok(HttpResponse {
+                    status: 200,
+                    headers: HeaderMap::new(),
+                    body: body.to_string().into_bytes(),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn page_number() -> anyhow::Result<()> {
+        let pages = Pages::default();
+        let records: Vec<_> = pages
+            .client()
+            .paginate(
+                DescribeDomainRecords::new()
+                    .set_domain_name("example.com")
+                    .set_page_size(2),
+            )
+            .concurrency(4)
+            .items()
+            .map_ok(|record| record.record_id)
+            .try_collect()
+            .await?;
+        assert_eq!(records, ["1", "2", "3", "4", "5"]);
+        assert_eq!(pages.requests().len(), 3);
+
+        let pages = Pages::default();
+        let responses: Vec<_> = pages
+            .client()
+            .paginate(
+                DescribeDomainRecords::new()
+                    .set_domain_name("example.com")
+                    .set_page_size(2),
+            )
+            .max_pages(2)
+            .pages()
+            .try_collect()
+            .await?;
+        assert_eq!(responses.len(), 2);
+        assert_eq!(pages.requests().len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn next_token() -> anyhow::Result<()> {
+        let pages = Pages::default();
+        let instances: Vec<_> = pages
+            .client()
+            .paginate(
+                DescribeInstanceBill::new().set_billing_cycle(YearMonth::new(2024, 1).unwrap()),
+            )
+            .concurrency(4)
+            .items()
+            .map_ok(|item| item.instance_id)
+            .try_collect()
+            .await?;
+        assert_eq!(instances, ["", "a", "b"]);
+        assert!(pages.requests()[2].contains("NextToken=b"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn offset() -> anyhow::Result<()> {
+        let pages = Pages::default();
+        let logs: Vec<_> = pages
+            .client()
+            .paginate(
+                GetLogsV2::new()
+                    .set_project("my-project")
+                    .set_logstore("my-logstore")
+                    .set_from(Utc.timestamp_opt(1704067200, 0).unwrap())
+                    .set_to(Utc.timestamp_opt(1704153600, 0).unwrap())
+                    .set_line(2),
+            )
+            .items()
+            .map_ok(|log| log.request_uri)
+            .try_collect()
+            .await?;
+        assert_eq!(logs, ["/0", "/1", "/2"]);
+        let requests = pages.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].contains(r#""offset":"2""#));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn page_number_arms() -> anyhow::Result<()> {
+        let pages = Pages::default();
+        let items: Vec<_> = pages
+            .client()
+            .paginate(
+                ListSyntheticDetail::new()
+                    .set_synthetic_type(2)
+                    .set_detail(SyntheticDetail::DownloadList)
+                    .set_start_time(Utc.timestamp_opt(1703980800, 0).unwrap())
+                    .set_end_time(Utc.timestamp_opt(1704067200, 0).unwrap())
+                    .set_filters("f124d885bcbc4d78a7fcb3a020b6ad66")
+                    .set_page_size(2),
+            )
+            .items()
+            .try_collect()
+            .await?;
+        assert_eq!(
+            items
+                .iter()
+                .map(|item| (item.data_id.as_str(), item.time))
+                .collect::<Vec<_>>(),
+            [
+                ("data-0", 1704003600),
+                ("data-1", 1704003660),
+                ("data-2", 1704003720)
+            ]
+        );
+        assert!(items
+            .iter()
+            .all(|item| item.task_name == "example-download"));
+        let requests = pages.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].contains("&Page=1&PageSize=2&"));
+        assert!(requests[1].contains("&Page=2&PageSize=2&"));
+        Ok(())
+    }
+
+    #[test]
+    fn pages() {
+        assert_eq!(page_count(5, 2), 3);
+        assert_eq!(page_count(4, 2), 2);
+        assert_eq!(page_count(0, 2), 0);
+        assert_eq!(
+            DescribeInstanceBill::first_page(),
+            super::Page::Token(String::new())
+        );
+        let _ = DescribeDomainRecords::new().paginate().max_pages(1).items();
+    }
+}
@@ -0,0 +1,346 @@
+use std::fmt;
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, TimeZone, Utc};
+
+/// 请求参数的值，派生 `Api` 生成的 setter 通过它把字段序列化为查询参数或表单参数
+///
+/// 整数和浮点数按十进制、布尔值按 `true`/`false`、日期按 `YYYY-MM-DD`、年月按 `YYYY-MM`、时间按 UTC 的
+/// `YYYY-MM-DDThh:mm:ssZ` 序列化。其他格式通过字段的 `format` 属性和 `FormatParam` 指定
+pub trait ParamValue {
+    fn to_param(&self) -> String;
+}
+
+impl ParamValue for String {
+    fn to_param(&self) -> String {
+        self.clone()
+    }
+}
+
+impl ParamValue for &str {
+    fn to_param(&self) -> String {
+        self.to_string()
+    }
+}
+
+macro_rules! display_param {
+    ($($ty:ty),*) => {
+        $(
+            impl ParamValue for $ty {
+                fn to_param(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+display_param!(i32, i64, u32, u64, f64, bool);
+
+/// 定义取值固定的请求参数，`Other` 用于发送未列出的新取值
+///
+/// 从字符串转换时不区分大小写地匹配已知的取值，其他字符串原样保存在 `Other` 中
+macro_rules! param_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            ///未列出的取值，原样发送
+            Other(String),
+        }
+
+        impl $name {
+            ///参数值
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Other(value) => value,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                $(
+                    if value.eq_ignore_ascii_case($value) {
+                        return $name::$variant;
+                    }
+                )*
+                $name::Other(value.to_string())
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                $name::from(value.as_str())
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl $crate::aliapis::param::ParamValue for $name {
+            fn to_param(&self) -> String {
+                self.as_str().to_string()
+            }
+        }
+    };
+}
+#[cfg(any(
+    feature = "dns",
+    feature = "billing",
+    feature = "sms",
+    feature = "arms"
+))]
+pub(crate) use param_enum;
+
+param_enum! {
+    /// 排序方向
+    pub enum SortDirection {
+        ///升序
+        Asc = "ASC",
+        ///降序
+        Desc = "DESC",
+    }
+}
+
+impl ParamValue for NaiveDate {
+    fn to_param(&self) -> String {
+        self.format("%Y-%m-%d").to_string()
+    }
+}
+
+impl ParamValue for DateTime<Utc> {
+    fn to_param(&self) -> String {
+        self.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+    }
+}
+
+/// 年月，例如账单的账期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct YearMonth {
+    year: i32,
+    month: u32,
+}
+
+impl YearMonth {
+    ///月份不在 1 到 12 之间或年份超出 `NaiveDate` 可表示的范围时返回 `None`
+    pub fn new(year: i32, month: u32) -> Option<Self> {
+        NaiveDate::from_ymd_opt(year, month, 1).map(Self::from)
+    }
+    ///时间在所在时区的年月，账期按北京时间计算时使用 `china_standard_time()`
+    pub fn of<Tz: TimeZone>(time: &DateTime<Tz>) -> Self {
+        Self::from(time.date_naive())
+    }
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+    ///上一个月，已是 `NaiveDate` 可表示的最早月份时返回 `None`
+    pub fn previous(&self) -> Option<Self> {
+        match self.month {
+            1 => Self::new(self.year.checked_sub(1)?, 12),
+            month => Self::new(self.year, month - 1),
+        }
+    }
+    ///当月的第一天
+    pub fn first_day(&self) -> NaiveDate {
+        NaiveDate::from_ymd_opt(self.year, self.month, 1).expect("checked by YearMonth::new")
+    }
+}
+
+impl From<NaiveDate> for YearMonth {
+    fn from(date: NaiveDate) -> Self {
+        Self {
+            year: date.year(),
+            month: date.month(),
+        }
+    }
+}
+
+impl fmt::Display for YearMonth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month)
+    }
+}
+
+impl ParamValue for YearMonth {
+    fn to_param(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// 北京时间（UTC+8），账单和短信等接口按北京时间解释日期和时间
+pub fn china_standard_time() -> FixedOffset {
+    FixedOffset::east_opt(8 * 3600).expect("offset is in range")
+}
+
+/// 按接口要求的格式序列化日期和时间，派生 `Api` 时由字段的 `format`、`offset` 属性指定
+///
+/// `format` 为 strftime 格式，时间也可以使用 `"seconds"`、`"millis"` 序列化为 Unix 时间戳；
+/// 时间先转换到 `offset` 表示的时区再格式化
+pub trait FormatParam {
+    fn format_param(&self, format: &str, offset: FixedOffset) -> String;
+}
+
+impl FormatParam for NaiveDate {
+    fn format_param(&self, format: &str, _offset: FixedOffset) -> String {
+        self.format(format).to_string()
+    }
+}
+
+impl FormatParam for YearMonth {
+    fn format_param(&self, format: &str, _offset: FixedOffset) -> String {
+        self.first_day().format(format).to_string()
+    }
+}
+
+impl FormatParam for DateTime<Utc> {
+    fn format_param(&self, format: &str, offset: FixedOffset) -> String {
+        match format {
+            "seconds" => self.timestamp().to_string(),
+            "millis" => self.timestamp_millis().to_string(),
+            format => self.with_timezone(&offset).format(format).to_string(),
+        }
+    }
+}
+
+// 派生 `Api` 生成的代码使用，offset 已在编译时检查
+#[doc(hidden)]
+pub fn utc_offset(seconds: i32) -> FixedOffset {
+    FixedOffset::east_opt(seconds).expect("offset is checked by derive(Api)")
+}
+
+// 校验整数参数的取值范围，未设置时不检查
+#[cfg(any(test, feature = "billing", feature = "sms", feature = "arms"))]
+pub(crate) fn check_range(
+    error: &mut crate::aliapis::error::ValidationError,
+    name: &str,
+    value: Option<i64>,
+    range: std::ops::RangeInclusive<i64>,
+) {
+    match value {
+        Some(value) if !range.contains(&value) => error.invalid(
+            name,
+            format!(
+                "must be between {} and {}, got {}",
+                range.start(),
+                range.end(),
+                value
+            ),
+        ),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Datelike, NaiveDate, TimeZone, Utc};
+
+    use super::{
+        check_range, china_standard_time, FormatParam, ParamValue, SortDirection, YearMonth,
+    };
+    use crate::aliapis::error::{ParamProblem, ValidationError};
+
+    #[test]
+    fn serialize() {
+        assert_eq!("a b".to_param(), "a b");
+        assert_eq!(500i64.to_param(), "500");
+        assert_eq!(0.5f64.to_param(), "0.5");
+        assert_eq!(true.to_param(), "true");
+        assert_eq!(
+            NaiveDate::from_ymd_opt(2024, 1, 2).unwrap().to_param(),
+            "2024-01-02"
+        );
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5)
+                .unwrap()
+                .to_param(),
+            "2024-01-02T03:04:05Z"
+        );
+        assert_eq!(YearMonth::new(2024, 1).unwrap().to_param(), "2024-01");
+    }
+
+    #[test]
+    fn format() {
+        let cst = china_standard_time();
+        let time = Utc.with_ymd_and_hms(2023, 12, 31, 16, 0, 0).unwrap();
+        assert_eq!(
+            time.format_param("%Y-%m-%d %H:%M:%S", cst),
+            "2024-01-01 00:00:00"
+        );
+        assert_eq!(time.format_param("seconds", cst), "1704038400");
+        assert_eq!(time.format_param("millis", cst), "1704038400000");
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        assert_eq!(date.format_param("%Y%m%d", cst), "20240102");
+
+        // 同一时刻在 UTC 和北京时间属于不同的账期
+        assert_eq!(YearMonth::of(&time).to_string(), "2023-12");
+        assert_eq!(
+            YearMonth::of(&time.with_timezone(&cst)).to_string(),
+            "2024-01"
+        );
+        assert_eq!(
+            YearMonth::new(2024, 1)
+                .unwrap()
+                .previous()
+                .unwrap()
+                .to_string(),
+            "2023-12"
+        );
+        assert_eq!(YearMonth::new(2024, 13), None);
+    }
+
+    #[test]
+    fn year_month_bounds() {
+        assert_eq!(YearMonth::new(300_000, 1), None);
+        assert_eq!(YearMonth::new(i32::MIN, 1), None);
+
+        let min = YearMonth::from(NaiveDate::MIN);
+        let max = YearMonth::from(NaiveDate::MAX);
+        assert_eq!(YearMonth::new(min.year(), min.month()), Some(min));
+        assert_eq!(YearMonth::new(max.year(), max.month()), Some(max));
+        assert_eq!(min.previous(), None);
+        assert_eq!(max.previous().unwrap().first_day().month(), 11);
+        assert_eq!(
+            max.format_param("%Y-%m", china_standard_time()),
+            max.first_day().format("%Y-%m").to_string()
+        );
+    }
+
+    #[test]
+    fn param_enum() {
+        assert_eq!(SortDirection::from("asc"), SortDirection::Asc);
+        assert_eq!(SortDirection::Desc.to_param(), "DESC");
+        let other = SortDirection::from("RANDOM");
+        assert_eq!(other, SortDirection::Other("RANDOM".to_string()));
+        assert_eq!(other.to_string(), "RANDOM");
+    }
+
+    #[test]
+    fn check() {
+        let mut error = ValidationError::new("Test");
+        check_range(&mut error, "Size", Some(300), 1..=300);
+        check_range(&mut error, "Size", None, 1..=300);
+        assert!(error.clone().into_result().is_ok());
+
+        check_range(&mut error, "Size", Some(301), 1..=300);
+        assert_eq!(
+            error.problems,
+            [ParamProblem::Invalid {
+                name: "Size".to_string(),
+                reason: "must be between 1 and 300, got 301".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use ordermap::OrderMap;
+
+use crate::aliapis::{
+    credential::Credential,
+    error::AliError,
+    sign::{percent_encode, RequestHeader, SignatureVersion},
+};
+
+/// 脱敏时替换敏感内容使用的字符串
+pub const REDACTED: &str = "***";
+
+/// 签名后未发送的请求
+///
+/// 通过 `Api::prepare` 或 `AliClient::prepare` 得到，包含实际发送的方法、URL、header 和 body，
+/// 以及签名使用的规范化请求和 StringToSign，可以渲染为 curl 命令在其他环境重放
+#[derive(Debug, Clone)]
+pub struct PreparedRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: OrderMap<String, String>,
+    pub body: Vec<u8>,
+    /// 规范化请求，V2 签名与 StringToSign 相同
+    pub canonical_request: String,
+    pub string_to_sign: String,
+    // AccessKeyId、STS token 和签名，脱敏时替换
+    secrets: Vec<String>,
+}
+
+impl PreparedRequest {
+    pub(crate) fn new(request: RequestHeader, credential: &Credential) -> Result<Self, AliError> {
+        // 与发送时一样检查方法和 header，无法发送的请求不返回
+        request.http_parts()?;
+        let signature = match request.signature_version {
+            SignatureVersion::V3 => request
+                .headers
+                .get("Authorization")
+                .and_then(|authorization| authorization.rsplit_once("Signature="))
+                .map(|(_, signature)| signature.to_string()),
+            SignatureVersion::RpcV2 => request.query_param.get("Signature").cloned(),
+        };
+        let mut secrets = Vec::new();
+        for secret in [
+            Some(credential.access_key_id.clone()),
+            credential.security_token.clone(),
+            signature,
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|secret| !secret.is_empty())
+        {
+            // URL 中的值编码一次，V2 的 StringToSign 中编码两次
+            let encoded = percent_encode(&secret);
+            secrets.push(percent_encode(&encoded));
+            secrets.push(encoded);
+            secrets.push(secret);
+        }
+        secrets.sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+        secrets.dedup();
+
+        Ok(PreparedRequest {
+            method: request.http_method.clone(),
+            url: request.url(),
+            canonical_request: request.canonical_request_string(),
+            string_to_sign: request.string_to_sign(),
+            body: request.body.to_bytes(),
+            headers: request.headers,
+            secrets,
+        })
+    }
+
+    ///返回 AccessKeyId、STS token 和签名替换为 `***` 后的副本，用于记录日志或分享
+    ///
+    ///URL 中 V2 签名的 AccessKeyId、SecurityToken 和 Signature 参数按名称脱敏
+    pub fn redacted(&self) -> PreparedRequest {
+        let redact = |value: &str| {
+            self.secrets
+                .iter()
+                .fold(value.to_string(), |value, secret| {
+                    value.replace(secret.as_str(), REDACTED)
+                })
+        };
+        PreparedRequest {
+            method: self.method.clone(),
+            url: redact(&redact_params(&self.url)),
+            headers: self
+                .headers
+                .iter()
+                .map(|(k, v)| (k.clone(), redact(v)))
+                .collect(),
+            body: self.body.clone(),
+            canonical_request: redact(&self.canonical_request),
+            string_to_sign: redact(&self.string_to_sign),
+            secrets: Vec::new(),
+        }
+    }
+
+    ///渲染为 curl 命令
+    ///
+    ///命令中包含 AccessKeyId、STS token 和签名，记录日志或分享前先调用 `redacted()`。
+    ///声明了 gzip 的 Accept-Encoding 时加上 `--compressed`，不是 UTF-8 的 body 通过 printf 从标准输入传入
+    pub fn to_curl(&self) -> String {
+        let mut parts = vec![format!(
+            "curl -X {} {}",
+            self.method,
+            shell_quote(&self.url)
+        )];
+        for (k, v) in &self.headers {
+            parts.push(format!("-H {}", shell_quote(&format!("{}: {}", k, v))));
+        }
+        let gzip = self.headers.iter().any(|(k, v)| {
+            k.eq_ignore_ascii_case("accept-encoding") && v.to_ascii_lowercase().contains("gzip")
+        });
+        if gzip {
+            parts.push("--compressed".to_string());
+        }
+        let mut stdin = None;
+        if !self.body.is_empty() {
+            match std::str::from_utf8(&self.body) {
+                Ok(body) => parts.push(format!("--data-raw {}", shell_quote(body))),
+                Err(_) => {
+                    // printf 的八进制转义可以表示任意字节，包括 NUL
+                    let escaped: String =
+                        self.body.iter().map(|b| format!("\\{:03o}", b)).collect();
+                    stdin = Some(format!("printf '{}' | ", escaped));
+                    parts.push("--data-binary @-".to_string());
+                }
+            }
+        }
+        format!("{}{}", stdin.unwrap_or_default(), parts.join(" \\\n  "))
+    }
+}
+
+// V2 签名放在查询参数中的凭证和签名，与录制时去掉的参数一致
+const SECRET_PARAMS: [&str; 3] = ["AccessKeyId", "SecurityToken", "Signature"];
+
+// 按名称替换 URL 查询参数中的凭证和签名
+fn redact_params(url: &str) -> String {
+    let Some((path, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let query = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if SECRET_PARAMS.contains(&key) => format!("{}={}", key, REDACTED),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{}?{}", path, query)
+}
+
+// 使用单引号包裹，内部的单引号转义为 '\''
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+// 测试使用 DNS 和短信的接口
+#[cfg(all(test, feature = "dns", feature = "sms"))]
+mod tests {
+    use chrono::DateTime;
+
+    use ordermap::OrderMap;
+
+    use super::{shell_quote, PreparedRequest};
+    use crate::aliapis::{
+        client::AliClient,
+        credential::StaticProvider,
+        dns::{AddDomainRecord, RecordType},
+        error::AliError,
+        sign::{Api, FixedClock, FixedNonce},
+        sms::QuerySendStatistics,
+    };
+
+    fn client(credentials: StaticProvider) -> AliClient {
+        let clock = FixedClock(
+            DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+                .unwrap()
+                .to_utc(),
+        );
+        AliClient::builder()
+            .credentials(credentials)
+            .clock(clock)
+            .nonce_generator(FixedNonce("3156853299f313e23d1673dc12e1703d".to_string()))
+            .build()
+            .unwrap()
+    }
+
+    fn add_domain_record() -> AddDomainRecord {
+        AddDomainRecord::new()
+            .set_domain_name("example.com")
+            .set_rr("test")
+            .set_type(RecordType::Cname)
+            .set_value("www.example.com")
+    }
+
+    #[tokio::test]
+    async fn prepare() -> anyhow::Result<()> {
+        let client = client(StaticProvider::new("testid", "testsecret"));
+        let prepared = client.prepare(add_domain_record()).await?;
+
+        // 与快照测试的签名一致
+        assert_eq!(prepared.method, "GET");
+        assert_eq!(
+            prepared.url,
+            "https://alidns.cn-hangzhou.aliyuncs.com/?DomainName=example.com&PageSize=100&RR=test&Type=CNAME&Value=www.example.com"
+        );
+        assert_eq!(
+            prepared.headers["Authorization"],
+            "ACS3-HMAC-SHA256 Credential=testid,SignedHeaders=host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version,Signature=03cbd35df8068189420a22d4ad5bf13dd44d55c113b7565f15b8a737a849f20b"
+        );
+        assert!(prepared
+            .canonical_request
+            .starts_with("GET\n/\nDomainName=example.com&PageSize=100"));
+        assert!(prepared.string_to_sign.starts_with("ACS3-HMAC-SHA256\n"));
+        assert!(prepared.body.is_empty());
+
+        // 校验失败时不会签名
+        let error = client.prepare(AddDomainRecord::new()).await.unwrap_err();
+        assert!(matches!(error, AliError::Validation(_)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn to_curl() -> anyhow::Result<()> {
+        let client = client(StaticProvider::new("testid", "testsecret"));
+        let prepared = client
+            .prepare(
+                QuerySendStatistics::new()
+                    .set_is_globe(1)
+                    .set_start_date(chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+                    .set_end_date(chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+                    .set_sign_name("阿里云's"),
+            )
+            .await?;
+        let curl = prepared.to_curl();
+        assert!(curl.starts_with("curl -X POST 'https://dysmsapi.aliyuncs.com/' \\\n  -H "));
+        assert!(curl.contains("\\\n  -H 'content-type: application/x-www-form-urlencoded' \\\n"));
+        assert!(curl.contains("Credential=testid,"));
+        assert!(curl.ends_with(
+            "--data-raw 'EndDate=20240101&IsGlobe=1&PageIndex=1&PageSize=50&SignName=%E9%98%BF%E9%87%8C%E4%BA%91%27s&StartDate=20240101'"
+        ));
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn redacted() -> anyhow::Result<()> {
+        let client =
+            client(StaticProvider::new("STS.id", "testsecret").with_security_token("sts-token+/="));
+        let prepared = client.prepare(add_domain_record()).await?;
+        let redacted = prepared.redacted();
+        assert_eq!(redacted.headers["x-acs-security-token"], "***");
+        assert!(redacted.headers["Authorization"].starts_with("ACS3-HMAC-SHA256 Credential=***,"));
+        assert!(redacted.headers["Authorization"].ends_with(",Signature=***"));
+        assert!(redacted
+            .canonical_request
+            .contains("x-acs-security-token:***\n"));
+
+        let curl = redacted.to_curl();
+        for secret in ["STS.id", "sts-token", "testsecret"] {
+            assert!(!curl.contains(secret), "{}", curl);
+        }
+        // 未脱敏的请求不受影响
+        assert_eq!(prepared.headers["x-acs-security-token"], "sts-token+/=");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn redacted_rpc_v2() -> anyhow::Result<()> {
+        #[derive(Debug, Clone, api_derive::Api)]
+        #[api(
+            action = "DescribeRegions",
+            version = "2014-05-26",
+            endpoint = "ecs.aliyuncs.com",
+            response = "serde_json::Value",
+            signature = "v2"
+        )]
+        struct Regions {
+            #[api(name = "RegionId")]
+            region_id: Option<String>,
+        }
+
+        let client =
+            client(StaticProvider::new("testid", "testsecret").with_security_token("token+/="));
+        let prepared = client
+            .prepare(Regions::new().set_region_id("cn-hangzhou"))
+            .await?;
+        assert!(prepared.url.contains("AccessKeyId=testid&"));
+        assert!(prepared
+            .string_to_sign
+            .contains("SecurityToken%3Dtoken%252B%252F%253D"));
+
+        let redacted = prepared.redacted();
+        assert!(redacted.url.contains("AccessKeyId=***&"));
+        assert!(redacted.url.contains("&SecurityToken=***&"));
+        assert!(redacted.url.contains("&Signature=***&"));
+        assert!(redacted.string_to_sign.contains("AccessKeyId%3D***%26"));
+        assert!(redacted.string_to_sign.contains("SecurityToken%3D***%26"));
+        assert_eq!(redacted.canonical_request, redacted.string_to_sign);
+        Ok(())
+    }
+
+    #[test]
+    fn to_curl_compressed_and_binary() {
+        let mut headers = OrderMap::new();
+        headers.insert("Accept-Encoding".to_string(), "deflate, gzip".to_string());
+        let request = PreparedRequest {
+            method: "POST".to_string(),
+            url:
+                "https://example.com/?AccessKeyId=id&Action=PutLogs&SecurityToken=a%2Bb&Signature=c"
+                    .to_string(),
+            headers,
+            body: vec![0x1f, 0x8b, 0x00, b'\''],
+            canonical_request: String::new(),
+            string_to_sign: String::new(),
+            secrets: Vec::new(),
+        };
+        assert_eq!(
+            request.to_curl(),
+            "printf '\\037\\213\\000\\047' | curl -X POST 'https://example.com/?AccessKeyId=id&Action=PutLogs&SecurityToken=a%2Bb&Signature=c' \\\n  \
+             -H 'Accept-Encoding: deflate, gzip' \\\n  \
+             --compressed \\\n  \
+             --data-binary @-"
+        );
+
+        // 没有记录下来的凭证也按参数名脱敏
+        assert_eq!(
+            request.redacted().url,
+            "https://example.com/?AccessKeyId=***&Action=PutLogs&SecurityToken=***&Signature=***"
+        );
+    }
+}
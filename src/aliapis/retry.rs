@@ -0,0 +1,218 @@
+use std::{fmt, io, sync::Arc, time::Duration};
+
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::aliapis::error::AliError;
+
+/// 判断错误是否可以重试
+pub type RetryClassifier = Arc<dyn Fn(&AliError) -> bool + Send + Sync>;
+
+/// 重试策略：指数退避加随机抖动
+///
+/// 每次重试都会重新生成 `x-acs-date`、`x-acs-signature-nonce` 并重新签名。
+/// 非幂等的请求（例如 AddDomainRecord）默认只在连接阶段失败时重试，避免重复创建资源，
+/// 可以通过 `retry_non_idempotent` 改为与幂等请求相同的判断
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    retry_non_idempotent: bool,
+    classifier: RetryClassifier,
+}
+
+impl Default for RetryPolicy {
+    ///最多尝试 3 次，退避从 200ms 开始，最长 10s
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            retry_non_idempotent: false,
+            classifier: Arc::new(is_retryable),
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .field("retry_non_idempotent", &self.retry_non_idempotent)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    ///不重试
+    pub fn none() -> Self {
+        Self::default().max_attempts(1)
+    }
+    ///最大尝试次数，包括第一次请求
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+    ///第一次重试前的等待时间，之后每次翻倍
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+    ///单次等待时间上限
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+    ///是否在等待时间上加随机抖动，默认开启
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+    ///非幂等的请求是否也按 classifier 重试，默认关闭，只在连接失败时重试。
+    ///服务端能按 ClientToken 等参数去重时可以开启
+    pub fn retry_non_idempotent(mut self, retry_non_idempotent: bool) -> Self {
+        self.retry_non_idempotent = retry_non_idempotent;
+        self
+    }
+    ///自定义可重试错误的判断，默认为 `is_retryable`
+    pub fn classifier(
+        mut self,
+        classifier: impl Fn(&AliError) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.classifier = Arc::new(classifier);
+        self
+    }
+
+    ///第 `attempt` 次请求失败后是否重试
+    pub fn should_retry(&self, attempt: u32, error: &AliError, idempotent: bool) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+        if idempotent || self.retry_non_idempotent {
+            (self.classifier)(error)
+        } else {
+            is_connect_error(error)
+        }
+    }
+
+    ///第 `attempt` 次请求失败后的等待时间，开启抖动时取 [delay/2, delay] 之间的随机值
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(31);
+        let delay = self.base_delay.saturating_mul(1 << exp).min(self.max_delay);
+        if !self.jitter {
+            return delay;
+        }
+        let mut bytes = [0u8; 4];
+        let random = match SystemRandom::new().fill(&mut bytes) {
+            Ok(()) => u32::from_le_bytes(bytes) as f64 / u32::MAX as f64,
+            Err(_) => 1.0,
+        };
+        delay.div_f64(2.0) + delay.div_f64(2.0).mul_f64(random)
+    }
+}
+
+/// 默认的可重试错误：流控、5xx、ServiceUnavailable、连接失败、超时和连接被重置
+pub fn is_retryable(error: &AliError) -> bool {
+    match error {
+        AliError::Transport(e) => e.is_connect() || e.is_timeout() || is_connection_reset(e),
+        AliError::Throttling(_) => true,
+        AliError::Service(e) => {
+            e.status >= 500
+                || matches!(
+                    e.code.as_str(),
+                    "ServiceUnavailable" | "ServiceUnavailableTemporary" | "InternalError"
+                )
+        }
+        _ => false,
+    }
+}
+
+/// 是否在建立连接阶段失败，此时请求一定没有到达服务端
+pub fn is_connect_error(error: &AliError) -> bool {
+    matches!(error, AliError::Transport(e) if e.is_connect())
+}
+
+fn is_connection_reset(error: &reqwest::Error) -> bool {
+    let mut source = std::error::Error::source(error);
+    while let Some(e) = source {
+        if let Some(io_error) = e.downcast_ref::<io::Error>() {
+            return matches!(
+                io_error.kind(),
+                io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::BrokenPipe
+            );
+        }
+        source = e.source();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use reqwest::header::HeaderMap;
+
+    use super::RetryPolicy;
+    use crate::aliapis::error::{AliError, ServiceError};
+
+    fn service_error(status: u16, body: &str) -> AliError {
+        AliError::from_service(ServiceError::from_response(
+            status,
+            &HeaderMap::new(),
+            body.as_bytes(),
+        ))
+    }
+
+    #[test]
+    fn backoff() {
+        let policy = RetryPolicy::new()
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(500))
+            .jitter(false);
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+
+        let policy = policy.jitter(true);
+        for attempt in 1..5 {
+            let delay = policy.backoff(attempt);
+            let max = policy.clone().jitter(false).backoff(attempt);
+            assert!(delay >= max / 2 && delay <= max, "{:?} {:?}", delay, max);
+        }
+    }
+
+    #[test]
+    fn should_retry() {
+        let policy = RetryPolicy::new().max_attempts(3);
+        let throttling = service_error(400, r#"{"Code":"Throttling.User"}"#);
+        let unavailable = service_error(503, r#"{"Code":"ServiceUnavailable"}"#);
+        let bad_request = service_error(400, r#"{"Code":"InvalidParameter"}"#);
+
+        assert!(policy.should_retry(1, &throttling, true));
+        assert!(policy.should_retry(2, &unavailable, true));
+        assert!(!policy.should_retry(3, &unavailable, true));
+        assert!(!policy.should_retry(1, &bad_request, true));
+        // 非幂等请求只在连接失败时重试
+        assert!(!policy.should_retry(1, &throttling, false));
+        assert!(!policy.should_retry(1, &unavailable, false));
+        let lenient = policy.clone().retry_non_idempotent(true);
+        assert!(lenient.should_retry(1, &throttling, false));
+        assert!(!lenient.should_retry(1, &bad_request, false));
+        assert!(!lenient.should_retry(3, &throttling, false));
+
+        let policy = policy.classifier(|e| e.code() == Some("InvalidParameter"));
+        assert!(policy.should_retry(1, &bad_request, true));
+        assert!(!RetryPolicy::none().should_retry(1, &throttling, true));
+    }
+}
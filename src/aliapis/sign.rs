@@ -1,23 +1,252 @@
-use chrono::Utc;
-use data_encoding::HEXLOWER;
+use chrono::{DateTime, Utc};
+use data_encoding::{BASE64, HEXLOWER};
 use ordermap::OrderMap;
-use reqwest::{header::HeaderMap, Error, Method, Response};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Method,
+};
 use ring::{
     digest::{self},
     hmac,
+    rand::SystemRandom,
+    signature::{self, RsaKeyPair},
 };
-use std::{env, future::Future, slice::from_raw_parts, str::from_utf8_unchecked};
+use serde::de::DeserializeOwned;
 use tracing::debug;
 use uuid::Uuid;
 
-#[derive(Debug)]
+use crate::aliapis::{
+    client::default_client,
+    credential::{Credential, CredentialError, CredentialProvider},
+    endpoint::{EndpointResolver, DEFAULT_REGION},
+    error::{AliError, ValidationError},
+    prepare::PreparedRequest,
+    sm3,
+    transport::{HttpRequest, HttpResponse},
+};
+
+/// 签名使用的时间来源，测试中可以替换为固定时间
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// x-acs-signature-nonce 的来源
+pub trait NonceGenerator: Send + Sync {
+    fn nonce(&self) -> String;
+}
+
+/// 系统时间
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// 随机生成 UUID v4 作为 nonce
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidNonce;
+
+impl NonceGenerator for UuidNonce {
+    fn nonce(&self) -> String {
+        Uuid::new_v4().to_string()
+    }
+}
+
+/// 固定时间，用于复现签名
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// 固定 nonce，用于复现签名
+#[derive(Debug, Clone)]
+pub struct FixedNonce(pub String);
+
+impl NonceGenerator for FixedNonce {
+    fn nonce(&self) -> String {
+        self.0.clone()
+    }
+}
+
+/// 签名版本
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SignatureVersion {
+    /// V3 签名，签名放在 Authorization header 中
+    #[default]
+    V3,
+    /// RPC 风格的 V2 签名（HMAC-SHA1），公共参数和 Signature 作为查询参数发送，
+    /// 用于只支持旧版签名的产品和 API 版本
+    RpcV2,
+}
+
+/// ACS3 签名算法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SignatureAlgorithm {
+    #[default]
+    HmacSha256,
+    /// 国密 SM3，payload 摘要使用 x-acs-content-sm3
+    HmacSm3,
+    /// 使用 RSA 私钥签名，AccessKeySecret 为 PEM 格式（PKCS#8 或 PKCS#1）的私钥
+    RsaSha256,
+}
+
+impl SignatureAlgorithm {
+    ///Authorization 中的算法名
+    pub fn name(&self) -> &'static str {
+        match self {
+            SignatureAlgorithm::HmacSha256 => "ACS3-HMAC-SHA256",
+            SignatureAlgorithm::HmacSm3 => "ACS3-HMAC-SM3",
+            SignatureAlgorithm::RsaSha256 => "ACS3-RSA-SHA256",
+        }
+    }
+    ///保存 payload 摘要的 header
+    pub fn content_header(&self) -> &'static str {
+        match self {
+            SignatureAlgorithm::HmacSm3 => "x-acs-content-sm3",
+            _ => "x-acs-content-sha256",
+        }
+    }
+    ///摘要的十六进制小写编码
+    pub fn hash(&self, data: &[u8]) -> String {
+        match self {
+            SignatureAlgorithm::HmacSm3 => HEXLOWER.encode(&sm3::sm3(data)),
+            _ => HEXLOWER.encode(digest::digest(&digest::SHA256, data).as_ref()),
+        }
+    }
+    ///使用 secret 对 string_to_sign 签名，返回十六进制小写编码
+    pub fn sign(&self, secret: &str, string_to_sign: &str) -> Result<String, CredentialError> {
+        let signature = match self {
+            SignatureAlgorithm::HmacSha256 => {
+                let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+                hmac::sign(&key, string_to_sign.as_bytes())
+                    .as_ref()
+                    .to_vec()
+            }
+            SignatureAlgorithm::HmacSm3 => {
+                sm3::hmac_sm3(secret.as_bytes(), string_to_sign.as_bytes()).to_vec()
+            }
+            SignatureAlgorithm::RsaSha256 => {
+                let key_pair = rsa_key_pair(secret)?;
+                let mut signature = vec![0; key_pair.public().modulus_len()];
+                key_pair
+                    .sign(
+                        &signature::RSA_PKCS1_SHA256,
+                        &SystemRandom::new(),
+                        string_to_sign.as_bytes(),
+                        &mut signature,
+                    )
+                    .map_err(|_| CredentialError::Parse("failed to sign with RSA key".into()))?;
+                signature
+            }
+        };
+        Ok(HEXLOWER.encode(&signature))
+    }
+}
+
+// 解析 PEM 或 base64 编码的 DER 私钥
+pub(crate) fn rsa_key_pair(secret: &str) -> Result<RsaKeyPair, CredentialError> {
+    let body = secret
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("-----"))
+        .collect::<String>();
+    let der = BASE64
+        .decode(body.as_bytes())
+        .map_err(|e| CredentialError::Parse(format!("invalid RSA private key: {}", e)))?;
+    RsaKeyPair::from_pkcs8(&der)
+        .or_else(|_| RsaKeyPair::from_der(&der))
+        .map_err(|e| CredentialError::Parse(format!("invalid RSA private key: {}", e)))
+}
+
+/// 请求体
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum RequestBody {
+    #[default]
+    Empty,
+    /// application/x-www-form-urlencoded，RPC 风格 POST 接口的参数
+    Form(OrderMap<String, String>),
+    /// application/json，ROA 风格接口的参数
+    Json(serde_json::Value),
+    /// application/octet-stream
+    Bytes(Vec<u8>),
+}
+
+impl RequestBody {
+    ///对应的 content-type，空 body 不发送 content-type
+    pub fn content_type(&self) -> Option<&'static str> {
+        match self {
+            RequestBody::Empty => None,
+            RequestBody::Form(_) => Some("application/x-www-form-urlencoded"),
+            RequestBody::Json(_) => Some("application/json"),
+            RequestBody::Bytes(_) => Some("application/octet-stream"),
+        }
+    }
+    ///实际发送的内容，签名时对同样的内容计算摘要
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            RequestBody::Empty => Vec::new(),
+            RequestBody::Form(form) => {
+                let mut params: Vec<(String, String)> = form
+                    .iter()
+                    .map(|(k, v)| (percent_encode(k), percent_encode(v)))
+                    .collect();
+                params.sort();
+                params
+                    .iter()
+                    .map(|(k, v)| format!("{}={}", k, v))
+                    .collect::<Vec<_>>()
+                    .join("&")
+                    .into_bytes()
+            }
+            RequestBody::Json(value) => value.to_string().into_bytes(),
+            RequestBody::Bytes(bytes) => bytes.clone(),
+        }
+    }
+}
+
+impl From<OrderMap<String, String>> for RequestBody {
+    fn from(form: OrderMap<String, String>) -> Self {
+        RequestBody::Form(form)
+    }
+}
+
+impl From<serde_json::Value> for RequestBody {
+    fn from(value: serde_json::Value) -> Self {
+        RequestBody::Json(value)
+    }
+}
+
+impl From<Vec<u8>> for RequestBody {
+    fn from(bytes: Vec<u8>) -> Self {
+        RequestBody::Bytes(bytes)
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct RequestHeader {
     pub http_method: String,
     pub canonical_uri: String,
     pub host: String,
+    /// 产品 code，客户端根据它和地域重新解析 host
+    pub product: Option<String>,
+    /// 加在解析出的 host 前面的部分，例如 SLS 的 project
+    pub host_prefix: Option<String>,
+    /// 接口未指定地域时由客户端按自身地域填充的查询参数，例如 ARMS 的 RegionId
+    pub region_param: Option<String>,
     pub headers: OrderMap<String, String>,
     pub query_param: OrderMap<String, String>,
-    pub body: String,
+    pub body: RequestBody,
+    /// 重复发送是否安全，非幂等的请求只在连接阶段失败时重试
+    pub idempotent: bool,
+    pub algorithm: SignatureAlgorithm,
+    pub signature_version: SignatureVersion,
 }
 
 impl Default for RequestHeader {
@@ -26,9 +255,15 @@ impl Default for RequestHeader {
             http_method: "GET".to_string().to_uppercase(),
             canonical_uri: "/".to_string(),
             host: Default::default(),
+            product: None,
+            host_prefix: None,
+            region_param: None,
             headers: Default::default(),
             query_param: Default::default(),
             body: Default::default(),
+            idempotent: true,
+            algorithm: SignatureAlgorithm::default(),
+            signature_version: SignatureVersion::default(),
         }
     }
 }
@@ -45,16 +280,6 @@ impl RequestHeader {
         headers.insert_sorted("host".to_owned(), host.clone());
         headers.insert_sorted("x-acs-action".to_owned(), x_acs_action.clone());
         headers.insert_sorted("x-acs-version".to_owned(), x_acs_version.clone());
-        // use UTC date
-        headers.insert_sorted(
-            "x-acs-date".to_owned(),
-            Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
-        );
-        // use uuid as random string
-        headers.insert_sorted(
-            "x-acs-signature-nonce".to_owned(),
-            Uuid::new_v4().to_string(),
-        );
         headers.insert_sorted("Authorization".to_string(), "".to_string());
 
         RequestHeader {
@@ -63,20 +288,91 @@ impl RequestHeader {
             query_param,
             ..Default::default()
         }
+        .stamp()
+    }
+    ///使用产品 code 创建请求，host 由默认的 `EndpointResolver` 按默认地域解析，
+    ///发送时客户端会按自身的地域和 endpoint 配置重新解析
+    pub fn with_product(
+        product: &str,
+        x_acs_action: String,
+        x_acs_version: String,
+        query_param: OrderMap<String, String>,
+    ) -> RequestHeader {
+        let host = EndpointResolver::default().resolve(product, DEFAULT_REGION);
+        RequestHeader {
+            product: Some(product.to_string()),
+            ..RequestHeader::new(host, x_acs_action, x_acs_version, query_param)
+        }
+    }
+    ///重新生成 x-acs-date 和 x-acs-signature-nonce，重试前需要重新生成并签名
+    pub fn stamp(self) -> Self {
+        self.stamp_with(&SystemClock, &UuidNonce)
+    }
+    ///使用指定的时间和 nonce 来源生成 x-acs-date 和 x-acs-signature-nonce
+    pub fn stamp_with(mut self, clock: &dyn Clock, nonce: &dyn NonceGenerator) -> Self {
+        // use UTC date
+        self.headers.insert_sorted(
+            "x-acs-date".to_owned(),
+            clock.now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        );
+        self.headers
+            .insert_sorted("x-acs-signature-nonce".to_owned(), nonce.nonce());
+        self
     }
     ///设置请求方法
     pub fn set_method(mut self, method: &str) -> Self {
         self.http_method = method.to_uppercase();
         self
     }
+    ///设置请求的 host
+    pub fn set_host(mut self, host: &str) -> Self {
+        self.host = host.to_string();
+        self.headers
+            .insert_sorted("host".to_owned(), host.to_string());
+        self
+    }
+    ///在 host 前加上前缀，客户端重新解析 host 时保留前缀，例如 SLS 的 `{project}.{endpoint}`
+    pub fn set_host_prefix(mut self, prefix: &str) -> Self {
+        let host = format!("{}.{}", prefix, self.host);
+        self.host_prefix = Some(prefix.to_string());
+        self.set_host(&host)
+    }
+    ///将查询参数 `name` 设置为默认地域，客户端发送时替换为自身的地域
+    pub fn set_region_param(mut self, name: &str) -> Self {
+        self.query_param
+            .insert_sorted(name.to_string(), DEFAULT_REGION.to_string());
+        self.region_param = Some(name.to_string());
+        self
+    }
+    ///设置签名算法，默认为 ACS3-HMAC-SHA256
+    pub fn set_algorithm(mut self, algorithm: SignatureAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+    ///设置签名版本，默认为 V3
+    pub fn set_signature_version(mut self, signature_version: SignatureVersion) -> Self {
+        self.signature_version = signature_version;
+        self
+    }
+    ///标记请求是否幂等，默认为幂等
+    pub fn set_idempotent(mut self, idempotent: bool) -> Self {
+        self.idempotent = idempotent;
+        self
+    }
     ///设置请求路径
     pub fn set_uri(mut self, uri: &str) -> Self {
         self.canonical_uri = uri.to_string();
         self
     }
-    ///设置body
-    pub fn set_body(mut self, body: serde_json::Value) -> Self {
-        self.body = body.to_string();
+    ///设置 body，同时设置对应的 content-type
+    pub fn set_body(mut self, body: impl Into<RequestBody>) -> Self {
+        self.body = body.into();
+        if let Some(content_type) = self.body.content_type() {
+            self.headers
+                .insert_sorted("content-type".to_owned(), content_type.to_owned());
+        } else {
+            self.headers.remove("content-type");
+        }
         self
     }
     /// 设置 accept_encoding
@@ -85,88 +381,52 @@ impl RequestHeader {
             .insert_sorted("Accept-Encoding".to_owned(), accept_encoding.into());
         self
     }
-    ///使用AK/SK 签名请求
-    pub fn sign(mut self) -> Self {
-        // get AK and SK from env
-        let access_key_secret = env::var("ALI_CLOUD_ACCESSKEY_SECRET").expect("can not get SK");
-        let access_key_id = env::var("ALI_CLOUD_ACCESSKEY_ID").expect("can not get AK");
-
-        let algorithm = "ACS3-HMAC-SHA256";
-
-        // format the params
-        let mut params_list = Vec::new();
-
-        for (k, v) in self.query_param.clone() {
-            params_list.push(format!(
-                "{}={}",
-                urlencoding::encode(&k),
-                urlencoding::encode(&v)
-            ));
+    ///从 provider 获取AK/SK 签名请求，获取不到凭证时返回错误
+    pub async fn sign(self, provider: &dyn CredentialProvider) -> Result<Self, CredentialError> {
+        let credential = provider.get_credential().await?;
+        self.sign_with(&credential)
+    }
+    ///使用指定的AK/SK 签名请求，RSA 私钥无法解析时返回错误
+    pub fn sign_with(mut self, credential: &Credential) -> Result<Self, CredentialError> {
+        if self.signature_version == SignatureVersion::RpcV2 {
+            return Ok(self.sign_rpc_v2(credential));
+        }
+        let access_key_id = &credential.access_key_id;
+        let access_key_secret = &credential.access_key_secret;
+        if let Some(security_token) = &credential.security_token {
+            self.headers
+                .insert_sorted("x-acs-security-token".to_string(), security_token.clone());
         }
-        let canonical_query_string = &params_list
-            .join("&")
-            .replace("+", "20%")
-            .replace("%5F", "_")
-            .replace("%2D", "-")
-            .replace("%2E", ".")
-            .replace("%7E", "~");
 
-        let request_body = self.body.to_string();
+        let algorithm = self.algorithm;
 
-        // get the hex encoding from the sha256 digest of payload
-        let hashed_request_payload = HEXLOWER
-            .encode(ring::digest::digest(&digest::SHA256, request_body.as_bytes()).as_ref());
+        // get the hex encoding from the digest of payload, which is exactly the bytes sent
+        let hashed_request_payload = algorithm.hash(&self.body.to_bytes());
         self.headers.insert_sorted(
-            "x-acs-content-sha256".to_string(),
-            hashed_request_payload.clone(),
+            algorithm.content_header().to_string(),
+            hashed_request_payload,
         );
 
-        // construct the headers
-        let mut filtered_headers = Vec::new();
-        let mut sign_headers = Vec::new();
-
-        for (k, v) in self.headers.clone() {
-            if k.to_lowercase().starts_with("x-acs-")
-                || k.to_lowercase().contains("host")
-                || k.to_lowercase().contains("content-type")
-            {
-                filtered_headers.push(format!("{}:{}", k, v));
-                sign_headers.push(k);
-            }
-        }
-
-        let signed_headers = sign_headers.join(";");
-
-        let canonical_headers = filtered_headers.join("\n");
-
-        let canonical_request = format!(
-            "{}\n{}\n{}\n{}\n\n{}\n{}",
-            self.http_method,
-            self.canonical_uri,
-            canonical_query_string,
-            canonical_headers,
-            signed_headers,
-            hashed_request_payload
-        )
-        .to_string();
+        let (canonical_request, signed_headers) = self.canonicalize();
 
         #[cfg(debug_assertions)]
         debug!("{:?}", canonical_request);
 
-        // first sha256 digest, then hex encoding
-        let hashed_canoical_request = HEXLOWER
-            .encode(ring::digest::digest(&digest::SHA256, canonical_request.as_bytes()).as_ref());
-        let string_to_sign = format!("{}\n{}", algorithm, hashed_canoical_request);
+        // first digest, then hex encoding
+        let hashed_canoical_request = algorithm.hash(canonical_request.as_bytes());
+        let string_to_sign = format!("{}\n{}", algorithm.name(), hashed_canoical_request);
         #[cfg(debug_assertions)]
         debug!("{}", string_to_sign);
 
         // sign the string
-        let key = hmac::Key::new(hmac::HMAC_SHA256, access_key_secret.as_bytes());
-        let signature = HEXLOWER.encode(hmac::sign(&key, string_to_sign.as_bytes()).as_ref());
+        let signature = algorithm.sign(access_key_secret, &string_to_sign)?;
 
         let authorization = format!(
             "{} Credential={},SignedHeaders={},Signature={}",
-            algorithm, access_key_id, signed_headers, signature
+            algorithm.name(),
+            access_key_id,
+            signed_headers,
+            signature
         );
 
         self.headers
@@ -174,51 +434,781 @@ impl RequestHeader {
             .and_modify(|auth| {
                 *auth = authorization;
             });
+        Ok(self)
+    }
+
+    // V2 签名：x-acs-* header 中的公共参数移到查询参数，对查询参数签名
+    fn sign_rpc_v2(mut self, credential: &Credential) -> Self {
+        for (header, param) in [
+            ("x-acs-action", "Action"),
+            ("x-acs-version", "Version"),
+            ("x-acs-date", "Timestamp"),
+            ("x-acs-signature-nonce", "SignatureNonce"),
+        ] {
+            if let Some(value) = self.headers.remove(header) {
+                self.query_param.insert_sorted(param.to_string(), value);
+            }
+        }
+        self.headers.remove("Authorization");
+
+        self.query_param
+            .entry("Format".to_string())
+            .or_insert_with(|| "JSON".to_string());
+        self.query_param
+            .insert_sorted("AccessKeyId".to_string(), credential.access_key_id.clone());
+        self.query_param
+            .insert_sorted("SignatureMethod".to_string(), "HMAC-SHA1".to_string());
+        self.query_param
+            .insert_sorted("SignatureVersion".to_string(), "1.0".to_string());
+        if let Some(security_token) = &credential.security_token {
+            self.query_param
+                .insert_sorted("SecurityToken".to_string(), security_token.clone());
+        }
+
+        let string_to_sign = self.rpc_string_to_sign();
+        #[cfg(debug_assertions)]
+        debug!("{}", string_to_sign);
+
+        let signature = rpc_v2_signature(&credential.access_key_secret, &string_to_sign);
+        self.query_param
+            .insert_sorted("Signature".to_string(), signature);
         self
     }
 
-    /// 发送请求到aliyun endpoint
-    pub fn send(self) -> impl Future<Output = Result<Response, Error>> {
-        let client = reqwest::Client::new();
+    // METHOD&%2F&encode(排序后的查询参数和表单参数)，不包含 Signature 本身
+    fn rpc_string_to_sign(&self) -> String {
+        let form = match &self.body {
+            RequestBody::Form(form) => Some(form),
+            _ => None,
+        };
+        build_rpc_string_to_sign(
+            &self.http_method,
+            self.query_param
+                .iter()
+                .chain(form.into_iter().flatten())
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        )
+    }
 
-        let url = format!("https://{}{}", self.host, self.canonical_uri);
+    ///签名使用的规范化请求，签名后调用可以得到与 Authorization 对应的内容；
+    ///V2 签名返回 StringToSign
+    pub fn canonical_request_string(&self) -> String {
+        match self.signature_version {
+            SignatureVersion::V3 => self.canonicalize().0,
+            SignatureVersion::RpcV2 => self.rpc_string_to_sign(),
+        }
+    }
 
-        let mut hashmap = HeaderMap::new();
-        for (k, v) in self.headers {
-            hashmap.insert(
-                get_str_at_location(k.as_ptr() as usize, k.len()),
-                v.parse().unwrap(),
-            );
+    ///签名使用的 StringToSign，V3 签名为算法名和规范化请求的摘要
+    pub fn string_to_sign(&self) -> String {
+        match self.signature_version {
+            SignatureVersion::V3 => {
+                let (canonical_request, _) = self.canonicalize();
+                format!(
+                    "{}\n{}",
+                    self.algorithm.name(),
+                    self.algorithm.hash(canonical_request.as_bytes())
+                )
+            }
+            SignatureVersion::RpcV2 => self.rpc_string_to_sign(),
+        }
+    }
+
+    // 返回规范化请求和参与签名的 header 列表
+    fn canonicalize(&self) -> (String, String) {
+        // construct the headers
+        let signed = self.headers.iter().filter(|(k, _)| {
+            k.to_lowercase().starts_with("x-acs-")
+                || k.to_lowercase().contains("host")
+                || k.to_lowercase().contains("content-type")
+        });
+
+        let hashed_request_payload = self
+            .headers
+            .get(self.algorithm.content_header())
+            .cloned()
+            .unwrap_or_else(|| self.algorithm.hash(&self.body.to_bytes()));
+
+        build_canonical_request(
+            &self.http_method,
+            &percent_encode_path(&self.canonical_uri),
+            &self.canonical_query_string(),
+            signed.map(|(k, v)| (k.as_str(), v.as_str())),
+            &hashed_request_payload,
+        )
+    }
+
+    ///按参数名排序、编码后的查询字符串，签名和实际发送的 URL 使用同一个字符串
+    pub fn canonical_query_string(&self) -> String {
+        let mut params: Vec<(String, String)> = self
+            .query_param
+            .iter()
+            .map(|(k, v)| (percent_encode(k), percent_encode(v)))
+            .collect();
+        params.sort();
+        params
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    ///请求的完整 URL，查询字符串与签名使用的一致
+    pub fn url(&self) -> String {
+        let mut url = format!(
+            "https://{}{}",
+            self.host,
+            percent_encode_path(&self.canonical_uri)
+        );
+        let query = self.canonical_query_string();
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query);
         }
-        let mut query_list = Vec::new();
-        for (k, v) in self.query_param {
-            query_list.push((k, v));
+        url
+    }
+
+    /// 转换为交给传输层发送的请求，URL 中的查询字符串与签名使用的一致
+    ///
+    /// 方法或 header 不是合法的 HTTP 内容时（例如参数值中含有换行）返回 `AliError::InvalidRequest`
+    pub fn into_http_request(self) -> Result<HttpRequest, AliError> {
+        let (method, headers) = self.http_parts()?;
+        Ok(HttpRequest {
+            method,
+            url: self.url(),
+            headers,
+            body: self.body.to_bytes(),
+        })
+    }
+
+    // 将方法和 header 转换为 http 类型，签名后未发送的请求也用它检查能否发送
+    pub(crate) fn http_parts(&self) -> Result<(Method, HeaderMap), AliError> {
+        let method = Method::from_bytes(self.http_method.as_bytes()).map_err(|_| {
+            AliError::InvalidRequest(format!("invalid HTTP method {:?}", self.http_method))
+        })?;
+        let mut headers = HeaderMap::new();
+        for (k, v) in &self.headers {
+            let name = HeaderName::from_bytes(k.as_bytes())
+                .map_err(|_| AliError::InvalidRequest(format!("invalid header name {:?}", k)))?;
+            let value = HeaderValue::from_str(v).map_err(|_| {
+                AliError::InvalidRequest(format!("invalid value of header {}: {:?}", k, v))
+            })?;
+            headers.insert(name, value);
         }
+        Ok((method, headers))
+    }
+}
 
-        let method = Method::from_bytes(self.http_method.as_bytes()).unwrap();
+/// 拼接规范化请求，返回规范化请求和 SignedHeaders
+///
+/// 路径和查询字符串需要已经编码，header 需要按名称排序；mock 服务端校验签名时使用同一个函数
+pub(crate) fn build_canonical_request<'a>(
+    method: &str,
+    encoded_uri: &str,
+    canonical_query: &str,
+    signed_headers: impl IntoIterator<Item = (&'a str, &'a str)>,
+    hashed_payload: &str,
+) -> (String, String) {
+    let mut canonical_headers = Vec::new();
+    let mut names = Vec::new();
+    for (k, v) in signed_headers {
+        canonical_headers.push(format!("{}:{}", k, v));
+        names.push(k);
+    }
+    let signed_headers = names.join(";");
 
-        let client = client
-            .request(method, url)
-            .headers(hashmap)
-            .query(&query_list)
-            .body(self.body.to_string());
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n\n{}\n{}",
+        method,
+        encoded_uri,
+        canonical_query,
+        canonical_headers.join("\n"),
+        signed_headers,
+        hashed_payload
+    );
+    (canonical_request, signed_headers)
+}
 
-        #[cfg(debug_assertions)]
-        debug!("{:#?}", client);
+/// 拼接 V2 签名的 StringToSign，参数为未编码的查询参数和表单参数，忽略其中的 Signature
+///
+/// mock 服务端校验 V2 签名时使用同一个函数
+pub(crate) fn build_rpc_string_to_sign<'a>(
+    method: &str,
+    params: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> String {
+    let mut params: Vec<(String, String)> = params
+        .into_iter()
+        .filter(|(k, _)| *k != "Signature")
+        .map(|(k, v)| (percent_encode(k), percent_encode(v)))
+        .collect();
+    params.sort();
+    let query = params
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&");
+    format!(
+        "{}&{}&{}",
+        method,
+        percent_encode("/"),
+        percent_encode(&query)
+    )
+}
 
-        client.send()
+/// V2 签名：以 `AccessKeySecret&` 为密钥计算 HMAC-SHA1，返回 base64 编码
+pub(crate) fn rpc_v2_signature(secret: &str, string_to_sign: &str) -> String {
+    let key = hmac::Key::new(
+        hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+        format!("{}&", secret).as_bytes(),
+    );
+    BASE64.encode(hmac::sign(&key, string_to_sign.as_bytes()).as_ref())
+}
+
+/// 按 RFC 3986 编码：除 `A-Z a-z 0-9 - _ . ~` 以外的字节都编码为大写的 `%XX`
+///
+/// 空格编码为 `%20`，`+` 编码为 `%2B`，`*` 编码为 `%2A`，非 ASCII 字符按 UTF-8 字节编码
+pub fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
     }
+    encoded
 }
 
-// 获取 static str
-fn get_str_at_location(pointer: usize, length: usize) -> &'static str {
-    // 使用裸指针需要 `unsafe{}` 语句块
-    unsafe { from_utf8_unchecked(from_raw_parts(pointer as *const u8, length)) }
+// 路径按段编码，保留分隔符 `/`
+fn percent_encode_path(path: &str) -> String {
+    path.split('/')
+        .map(percent_encode)
+        .collect::<Vec<_>>()
+        .join("/")
 }
 
+pub trait Api: Sized {
+    /// 接口返回的结构体
+    type Response: DeserializeOwned;
 
-pub trait Api {
     fn new() -> Self;
     fn name(&self) -> String;
-    fn send(self) -> impl std::future::Future<Output = Result<reqwest::Response, reqwest::Error>>;
+    /// 发送前检查必填参数和参数格式，`AliClient::execute` 在签名前调用
+    fn validate(&self) -> Result<(), ValidationError> {
+        Ok(())
+    }
+    /// 构造未签名的请求
+    fn canonical_request(self) -> RequestHeader;
+    /// 使用默认客户端发送请求，需要自定义配置时使用 `AliClient::execute`
+    fn send(self) -> impl std::future::Future<Output = Result<HttpResponse, AliError>> {
+        default_client().execute(self)
+    }
+    /// 使用默认客户端发送请求，并将返回解析为 `Self::Response`
+    fn send_typed(self) -> impl std::future::Future<Output = Result<Self::Response, AliError>> {
+        default_client().execute_typed(self)
+    }
+    /// 使用默认客户端校验参数并签名，返回签名后的请求而不发送
+    fn prepare(self) -> impl std::future::Future<Output = Result<PreparedRequest, AliError>> {
+        default_client().prepare(self)
+    }
+    /// 使用共享的同步客户端发送请求，需要开启 `blocking` feature
+    #[cfg(feature = "blocking")]
+    fn send_blocking(self) -> Result<HttpResponse, AliError> {
+        crate::aliapis::blocking::default_blocking_client()?.execute(self)
+    }
+    /// 使用共享的同步客户端发送请求，并将返回解析为 `Self::Response`
+    #[cfg(feature = "blocking")]
+    fn send_typed_blocking(self) -> Result<Self::Response, AliError> {
+        crate::aliapis::blocking::default_blocking_client()?.execute_typed(self)
+    }
+}
+
+/// 使用固定的时间、nonce 和 AK 签名，返回规范化请求和 Authorization，用于各接口的快照测试
+#[cfg(all(
+    test,
+    any(
+        feature = "dns",
+        feature = "billing",
+        feature = "sms",
+        feature = "arms",
+        feature = "sls"
+    )
+))]
+pub(crate) fn snapshot(request: RequestHeader) -> (String, String) {
+    let clock = FixedClock(
+        DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .to_utc(),
+    );
+    let nonce = FixedNonce("3156853299f313e23d1673dc12e1703d".to_string());
+    let request = request
+        .stamp_with(&clock, &nonce)
+        .sign_with(&Credential::new("testid", "testsecret"))
+        .unwrap();
+    (
+        request.canonical_request_string(),
+        request.headers["Authorization"].clone(),
+    )
+}
+
+/// 各接口共用的快照和回放测试
+///
+/// `canonical_request` 用 `snapshot` 签名 `snapshot` 给出的请求，检查规范化请求和签名；
+/// `works` 从 `fixtures/cassettes/{cassette}.json` 回放 `=>` 后的请求并解析为 `Api::Response`，
+/// 之后可以用 `response: |response| { .. }` 或 `error: |error| { .. }` 检查解析结果
+#[cfg(all(
+    test,
+    any(
+        feature = "dns",
+        feature = "billing",
+        feature = "sms",
+        feature = "arms",
+        feature = "sls"
+    )
+))]
+macro_rules! api_tests {
+    (
+        snapshot: $snapshot:expr,
+        canonical_request: $canonical_request:expr,
+        signature: $signature:expr,
+        cassette: $cassette:expr => $request:expr $(,)?
+    ) => {
+        $crate::aliapis::sign::api_tests! {
+            snapshot: $snapshot,
+            canonical_request: $canonical_request,
+            signature: $signature,
+            cassette: $cassette => $request,
+            response: |_response| {},
+        }
+    };
+    (
+        snapshot: $snapshot:expr,
+        canonical_request: $canonical_request:expr,
+        signature: $signature:expr,
+        cassette: $cassette:expr => $request:expr,
+        $outcome:ident: |$value:ident| $check:block $(,)?
+    ) => {
+        #[test]
+        fn canonical_request() {
+            let (canonical_request, authorization) = $crate::aliapis::sign::snapshot(
+                $crate::aliapis::sign::Api::canonical_request($snapshot),
+            );
+            assert_eq!(canonical_request, $canonical_request);
+            let signed_headers = canonical_request.lines().nth_back(1).unwrap_or_default();
+            assert_eq!(
+                authorization,
+                format!(
+                    "ACS3-HMAC-SHA256 Credential=testid,SignedHeaders={},Signature={}",
+                    signed_headers, $signature
+                )
+            );
+        }
+
+        #[tokio::test]
+        async fn works() {
+            let result = $crate::aliapis::transport::cassette_client($cassette)
+                .execute_typed($request)
+                .await;
+            let $value = $crate::aliapis::sign::api_tests!(@$outcome result);
+            $check
+        }
+    };
+    (@response $result:ident) => {
+        $result.expect("failed to replay the cassette")
+    };
+    (@error $result:ident) => {
+        $result.expect_err("the cassette should return an error")
+    };
+}
+#[cfg(all(
+    test,
+    any(
+        feature = "dns",
+        feature = "billing",
+        feature = "sms",
+        feature = "arms",
+        feature = "sls"
+    )
+))]
+pub(crate) use api_tests;
+
+#[cfg(test)]
+mod tests {
+    use ordermap::OrderMap;
+
+    use chrono::DateTime;
+
+    use super::{
+        FixedClock, FixedNonce, RequestBody, RequestHeader, SignatureAlgorithm, SignatureVersion,
+    };
+    use crate::aliapis::{
+        credential::{Credential, CredentialError},
+        error::AliError,
+    };
+
+    fn request() -> RequestHeader {
+        RequestHeader::new(
+            "alidns.cn-hangzhou.aliyuncs.com".to_string(),
+            "DescribeDomains".to_string(),
+            "2015-01-09".to_string(),
+            OrderMap::new(),
+        )
+    }
+
+    #[test]
+    fn security_token_is_signed() {
+        let credential = Credential::new("STS.id", "secret").with_security_token("token");
+        let request = request().sign_with(&credential).unwrap();
+
+        assert_eq!(
+            request
+                .headers
+                .get("x-acs-security-token")
+                .map(String::as_str),
+            Some("token")
+        );
+        let authorization = &request.headers["Authorization"];
+        assert!(authorization.starts_with("ACS3-HMAC-SHA256 Credential=STS.id,"));
+        assert!(authorization.contains(
+            "SignedHeaders=host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-security-token;x-acs-signature-nonce;x-acs-version,"
+        ));
+    }
+
+    #[test]
+    fn into_http_request() {
+        let request = request().into_http_request().unwrap();
+        assert_eq!(request.method, reqwest::Method::GET);
+        assert_eq!(request.headers["x-acs-action"], "DescribeDomains");
+
+        // 无法发送的 header 和方法返回错误，不会 panic
+        let mut invalid = request_with_header("x-acs-test", "a\nb");
+        let error = invalid.clone().into_http_request().unwrap_err();
+        assert!(matches!(error, AliError::InvalidRequest(_)), "{}", error);
+        assert_eq!(
+            error.to_string(),
+            "invalid request: invalid value of header x-acs-test: \"a\\nb\""
+        );
+        invalid.headers.clear();
+        let error = invalid
+            .set_method("GET POST")
+            .into_http_request()
+            .unwrap_err();
+        assert!(matches!(error, AliError::InvalidRequest(_)), "{}", error);
+        let error = request_with_header("x acs", "a")
+            .into_http_request()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid request: invalid header name \"x acs\""
+        );
+    }
+
+    fn request_with_header(name: &str, value: &str) -> RequestHeader {
+        let mut request = request();
+        request.headers.insert(name.to_string(), value.to_string());
+        request
+    }
+
+    // V3 签名文档中的示例：https://help.aliyun.com/zh/sdk/product-overview/v3-request-structure-and-signature
+    // 签名机制文档中的 RunInstances 示例
+    fn documented_request() -> RequestHeader {
+        let mut query = OrderMap::new();
+        query.insert_sorted(
+            "ImageId".to_string(),
+            "win2019_1809_x64_dtc_zh-cn_40G_alibase_20230811.vhd".to_string(),
+        );
+        query.insert_sorted("RegionId".to_string(), "cn-shanghai".to_string());
+        let mut request = RequestHeader::new(
+            "ecs.cn-shanghai.aliyuncs.com".to_string(),
+            "RunInstances".to_string(),
+            "2014-05-26".to_string(),
+            query,
+        )
+        .set_method("POST");
+        request
+            .headers
+            .insert_sorted("x-acs-date".to_string(), "2023-10-26T10:22:32Z".to_string());
+        request.headers.insert_sorted(
+            "x-acs-signature-nonce".to_string(),
+            "3156853299f313e23d1673dc12e1703d".to_string(),
+        );
+        request
+    }
+
+    #[test]
+    fn documented_example() {
+        let request = documented_request()
+            .sign_with(&Credential::new("YourAccessKeyId", "YourAccessKeySecret"))
+            .unwrap();
+
+        assert_eq!(
+            request.headers["Authorization"],
+            "ACS3-HMAC-SHA256 Credential=YourAccessKeyId,SignedHeaders=host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version,Signature=06563a9e1b43f5dfe96b81484da74bceab24a1d853912eee15083a6f0f3283c0"
+        );
+    }
+
+    #[test]
+    fn hmac_sm3() {
+        let request = documented_request()
+            .set_algorithm(SignatureAlgorithm::HmacSm3)
+            .sign_with(&Credential::new("YourAccessKeyId", "YourAccessKeySecret"))
+            .unwrap();
+
+        assert_eq!(
+            request.headers["x-acs-content-sm3"],
+            "1ab21d8355cfa17f8e61194831e81a8f22bec8c728fefb747ed035eb5082aa2b"
+        );
+        assert!(!request.headers.contains_key("x-acs-content-sha256"));
+        assert_eq!(
+            request.headers["Authorization"],
+            "ACS3-HMAC-SM3 Credential=YourAccessKeyId,SignedHeaders=host;x-acs-action;x-acs-content-sm3;x-acs-date;x-acs-signature-nonce;x-acs-version,Signature=8aeb57d483a13c9dddaf33673a6866a7a9b598c9fca15cda5eac848c023cd2db"
+        );
+    }
+
+    #[test]
+    fn rsa_sha256() {
+        let private_key = include_str!("../../fixtures/rsa_private_key.pem");
+        let request = documented_request()
+            .set_algorithm(SignatureAlgorithm::RsaSha256)
+            .sign_with(&Credential::new("YourAccessKeyId", private_key))
+            .unwrap();
+
+        // openssl dgst -sha256 -sign fixtures/rsa_private_key.pem
+        assert_eq!(
+            request.headers["Authorization"],
+            "ACS3-RSA-SHA256 Credential=YourAccessKeyId,SignedHeaders=host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version,Signature=747c305f17ef5adb2838620717b6a25df6b0bb00921e1994b70aea4a93c24d172d54a7733a1dd90674e0e25701570d719d179b989496e914e9ac9626b6b57c24f7a3dd5599aaf6c6f4e861084924a02ba70218cf0df3223b02b29c4382bb9892e8c2b355775ea8c283fad8dbdc663e12b5b6197f0923d64a212c415f4e260813c1fe415bb774e96e2a4f96b333279eed5a3fa6c7cfa40137529e799a774414eefb4506bf335cc494aadc5f8406ce1360a9f333d62c85c36efad7399bbb127c5fa4c76ddfd0f420a41b66ce26b97d4d2aeadc318a599edbee2cc9e713a9a7de864ed0902abc9b4394f0196cbbc8ba5abb5f163ecceabd1cd93e9c144f8838dbba"
+        );
+
+        let error = documented_request()
+            .set_algorithm(SignatureAlgorithm::RsaSha256)
+            .sign_with(&Credential::new("YourAccessKeyId", "not a key"))
+            .unwrap_err();
+        assert!(matches!(error, CredentialError::Parse(_)));
+    }
+
+    // V2 签名文档中的 DescribeRegions 示例
+    #[test]
+    fn rpc_v2_documented_example() {
+        let mut query = OrderMap::new();
+        query.insert_sorted("Format".to_string(), "XML".to_string());
+        let clock = FixedClock(
+            DateTime::parse_from_rfc3339("2016-02-23T12:46:24Z")
+                .unwrap()
+                .to_utc(),
+        );
+        let nonce = FixedNonce("3ee8c1b8-83d3-44af-a94f-4e0ad82fd6cf".to_string());
+        let request = RequestHeader::new(
+            "ecs.aliyuncs.com".to_string(),
+            "DescribeRegions".to_string(),
+            "2014-05-26".to_string(),
+            query,
+        )
+        .set_signature_version(SignatureVersion::RpcV2)
+        .stamp_with(&clock, &nonce)
+        .sign_with(&Credential::new("testid", "testsecret"))
+        .unwrap();
+
+        assert_eq!(
+            request.canonical_request_string(),
+            "GET&%2F&AccessKeyId%3Dtestid%26Action%3DDescribeRegions%26Format%3DXML%26SignatureMethod%3DHMAC-SHA1%26SignatureNonce%3D3ee8c1b8-83d3-44af-a94f-4e0ad82fd6cf%26SignatureVersion%3D1.0%26Timestamp%3D2016-02-23T12%253A46%253A24Z%26Version%3D2014-05-26"
+        );
+        assert_eq!(
+            request.query_param["Signature"],
+            "OLeaidS1JvxuMvnyHOwuJ+uX5qY="
+        );
+        assert!(!request.headers.contains_key("Authorization"));
+        assert!(!request.headers.keys().any(|k| k.starts_with("x-acs-")));
+        assert_eq!(
+            request.canonical_query_string(),
+            "AccessKeyId=testid&Action=DescribeRegions&Format=XML&Signature=OLeaidS1JvxuMvnyHOwuJ%2BuX5qY%3D&SignatureMethod=HMAC-SHA1&SignatureNonce=3ee8c1b8-83d3-44af-a94f-4e0ad82fd6cf&SignatureVersion=1.0&Timestamp=2016-02-23T12%3A46%3A24Z&Version=2014-05-26"
+        );
+    }
+
+    #[derive(Debug, Clone, api_derive::Api)]
+    #[api(
+        action = "DescribeRegions",
+        version = "2014-05-26",
+        endpoint = "ecs.aliyuncs.com",
+        method = "post",
+        body = "form",
+        idempotent = false,
+        default(PageSize = 10, AcceptLanguage = "zh-CN"),
+        response = "serde_json::Value",
+        validate_with = "check_charge_type"
+    )]
+    struct Regions {
+        /// 地域
+        #[api(name = "RegionId", required)]
+        region_id: Option<String>,
+        #[api(name = "InstanceChargeType")]
+        charge_type: Option<String>,
+        #[api(name = "PageSize")]
+        page_size: Option<i64>,
+        #[api(name = "DryRun")]
+        dry_run: Option<bool>,
+        #[api(name = "Type", setter = "set_record_type")]
+        r#type: Option<String>,
+        #[api(skip)]
+        note: String,
+    }
+
+    impl Regions {
+        fn check_charge_type(&self, error: &mut super::ValidationError) {
+            if let Some(charge_type) = &self.charge_type {
+                if !["PrePaid", "PostPaid"].contains(&charge_type.as_str()) {
+                    error.invalid("InstanceChargeType", "expected PrePaid or PostPaid");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn derive_attributes() {
+        use super::Api;
+
+        let api = Regions::new();
+        assert_eq!(api.name(), "DescribeRegions");
+        assert_eq!(api.note, "");
+        assert_eq!(api.parameters()["PageSize"], "10");
+
+        // 设置的字段覆盖默认值，整数和布尔值按统一的格式序列化
+        let parameters = Regions::new()
+            .set_region_id("cn-hangzhou")
+            .set_page_size(50)
+            .set_dry_run(true)
+            .set_record_type("A")
+            .parameters();
+        assert_eq!(
+            parameters
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect::<Vec<_>>(),
+            [
+                "AcceptLanguage=zh-CN",
+                "DryRun=true",
+                "PageSize=50",
+                "RegionId=cn-hangzhou",
+                "Type=A"
+            ]
+        );
+        assert!(Regions::new().charge_type.is_none());
+
+        // 先检查必填参数，再调用 validate_with 指定的方法
+        let error = Regions::new()
+            .set_charge_type("Spot")
+            .validate()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "DescribeRegions: missing required parameter RegionId; \
+             invalid parameter InstanceChargeType: expected PrePaid or PostPaid"
+        );
+        let valid = Regions::new()
+            .set_region_id("cn-hangzhou")
+            .set_charge_type("PostPaid");
+        assert!(valid.validate().is_ok());
+
+        let request = api.canonical_request();
+        assert_eq!(request.host, "ecs.aliyuncs.com");
+        assert_eq!(request.product, None);
+        assert_eq!(request.http_method, "POST");
+        assert_eq!(request.headers["x-acs-version"], "2014-05-26");
+        assert!(request.query_param.is_empty());
+        assert_eq!(request.body.to_bytes(), b"AcceptLanguage=zh-CN&PageSize=10");
+        assert!(!request.idempotent);
+    }
+
+    #[test]
+    fn request_body() {
+        let mut form = OrderMap::new();
+        form.insert_sorted("StartDate".to_string(), "20240101".to_string());
+        form.insert_sorted("IsGlobe".to_string(), "1".to_string());
+        form.insert_sorted("Template".to_string(), "a b+c".to_string());
+        let signed = request()
+            .set_method("POST")
+            .set_body(form)
+            .sign_with(&Credential::new("id", "secret"))
+            .unwrap();
+        assert_eq!(
+            signed.body.to_bytes(),
+            b"IsGlobe=1&StartDate=20240101&Template=a%20b%2Bc"
+        );
+        assert_eq!(
+            signed.headers["content-type"],
+            "application/x-www-form-urlencoded"
+        );
+        assert!(signed.headers["Authorization"].contains("content-type;host;"));
+        // 摘要基于实际发送的字节
+        assert_eq!(
+            signed.headers["x-acs-content-sha256"],
+            SignatureAlgorithm::HmacSha256.hash(b"IsGlobe=1&StartDate=20240101&Template=a%20b%2Bc")
+        );
+
+        let json = request().set_body(serde_json::json!({"from": "1"}));
+        assert_eq!(json.headers["content-type"], "application/json");
+        assert_eq!(json.body.to_bytes(), br#"{"from":"1"}"#);
+
+        let bytes = json.set_body(vec![0u8, 1, 2]);
+        assert_eq!(bytes.headers["content-type"], "application/octet-stream");
+        assert_eq!(bytes.body.to_bytes(), vec![0u8, 1, 2]);
+
+        let empty = bytes.set_body(RequestBody::Empty);
+        assert!(!empty.headers.contains_key("content-type"));
+        assert!(empty.body.to_bytes().is_empty());
+    }
+
+    #[test]
+    fn percent_encode() {
+        assert_eq!(super::percent_encode("AZaz09-_.~"), "AZaz09-_.~");
+        assert_eq!(super::percent_encode("a b+c*d/e"), "a%20b%2Bc%2Ad%2Fe");
+        assert_eq!(
+            super::percent_encode("阿里云"),
+            "%E9%98%BF%E9%87%8C%E4%BA%91"
+        );
+        assert_eq!(
+            super::percent_encode(r#"{"taskId":"1"}"#),
+            "%7B%22taskId%22%3A%221%22%7D"
+        );
+    }
+
+    #[test]
+    fn canonical_query_string() {
+        let mut query = OrderMap::new();
+        query.insert_sorted("TemplateName".to_string(), "验证码 模板+1*".to_string());
+        query.insert_sorted("Query".to_string(), "status: 200 and *".to_string());
+        query.insert_sorted("A".to_string(), "".to_string());
+        let request = RequestHeader::new(
+            "dysmsapi.aliyuncs.com".to_string(),
+            "QuerySmsTemplateList".to_string(),
+            "2017-05-25".to_string(),
+            query,
+        );
+
+        assert_eq!(
+            request.canonical_query_string(),
+            "A=&Query=status%3A%20200%20and%20%2A&TemplateName=%E9%AA%8C%E8%AF%81%E7%A0%81%20%E6%A8%A1%E6%9D%BF%2B1%2A"
+        );
+    }
+
+    #[test]
+    fn stamp_regenerates_nonce() {
+        let request = request();
+        let restamped = request.clone().stamp();
+
+        assert_ne!(
+            request.headers["x-acs-signature-nonce"],
+            restamped.headers["x-acs-signature-nonce"]
+        );
+    }
+
+    #[test]
+    fn no_security_token_for_long_term_key() {
+        let request = request()
+            .sign_with(&Credential::new("id", "secret"))
+            .unwrap();
+
+        assert!(!request.headers.contains_key("x-acs-security-token"));
+        assert!(!request.headers["Authorization"].contains("x-acs-security-token"));
+    }
 }
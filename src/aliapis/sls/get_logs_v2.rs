@@ -1,24 +1,30 @@
+use crate::aliapis::error::ValidationError;
+use crate::aliapis::paginate::{Page, Paginated};
 use crate::Api;
 use crate::RequestHeader;
+use chrono::{DateTime, Utc};
 use ordermap::OrderMap;
-use reqwest::Error;
-use reqwest::Response;
 use serde::Deserialize;
 use serde::Serialize;
-use std::future::Future;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct GetLogsV2 {
+    project: Option<String>,
+    custom_domain: Option<String>,
     uri: String,
     query_params: OrderMap<String, String>,
     body: serde_json::Map<String, serde_json::Value>,
 }
 
 impl GetLogsV2 {
-    ///project 名称。
+    ///project 名称，请求发送到 `{project}.{region}.log.aliyuncs.com`。
     pub fn set_project(mut self, project: impl Into<String>) -> Self {
-        self.query_params
-            .insert_sorted("project".into(), project.into());
+        self.project = Some(project.into());
+        self
+    }
+    ///绑定到 project 的自定义域名，设置后直接使用该域名，不再按 project 和地域解析。
+    pub fn set_custom_domain(mut self, domain: impl Into<String>) -> Self {
+        self.custom_domain = Some(domain.into());
         self
     }
     ///logstore 名称。
@@ -27,18 +33,20 @@ impl GetLogsV2 {
         self.uri = uri;
         self
     }
-    ///开始时间，timestamp，精确到秒。
-    pub fn set_from(mut self, from: i64) -> Self {
+    ///开始时间，以秒级时间戳发送。
+    pub fn set_from(mut self, from: DateTime<Utc>) -> Self {
         self.body.insert(
             "from".to_owned(),
-            serde_json::Value::String(from.to_string()),
+            serde_json::Value::String(from.timestamp().to_string()),
         );
         self
     }
-    ///结束时间，timestamp，精确到秒。
-    pub fn set_to(mut self, to: i64) -> Self {
-        self.body
-            .insert("to".to_owned(), serde_json::Value::String(to.to_string()));
+    ///结束时间，以秒级时间戳发送。
+    pub fn set_to(mut self, to: DateTime<Utc>) -> Self {
+        self.body.insert(
+            "to".to_owned(),
+            serde_json::Value::String(to.timestamp().to_string()),
+        );
         self
     }
     ///仅当 query 参数为查询语句时，该参数有效，表示请求返回的最大日志条数。最小值为 0，最大值为 100，默认值为 100。
@@ -112,10 +120,14 @@ impl GetLogsV2 {
 }
 
 impl Api for GetLogsV2 {
+    type Response = GetLogsV2Response;
+
     fn new() -> Self {
         let query_params = OrderMap::new();
         let body = serde_json::Map::new();
         Self {
+            project: None,
+            custom_domain: None,
             uri: "/logstores/{logstore}/logs".to_string(),
             query_params,
             body,
@@ -126,24 +138,88 @@ impl Api for GetLogsV2 {
         "GetLogsV2".to_string()
     }
 
-    fn send(self) -> impl Future<Output = Result<Response, Error>> {
-        RequestHeader::new(
-            super::ENDPOINT.to_string(),
-            self.name(),
-            super::VERSION.to_string(),
-            self.query_params,
-        )
-        .set_method("POST")
-        .set_accept_encoding("gzip")
-        .set_uri(&self.uri)
-        .set_body(serde_json::Value::Object(self.body))
-        .sign()
-        .send()
+    fn validate(&self) -> Result<(), ValidationError> {
+        let mut error = ValidationError::new(self.name());
+        if self.project.is_none() && self.custom_domain.is_none() {
+            error.missing("project");
+        }
+        if self.uri.contains("{logstore}") {
+            error.missing("logstore");
+        }
+        for name in ["from", "to"] {
+            if !self.body.contains_key(name) {
+                error.missing(name);
+            }
+        }
+        error.into_result()
+    }
+
+    fn canonical_request(self) -> RequestHeader {
+        let name = self.name();
+        let request = match self.custom_domain {
+            Some(domain) => {
+                RequestHeader::new(domain, name, super::VERSION.to_string(), self.query_params)
+            }
+            None => RequestHeader::with_product(
+                super::PRODUCT,
+                name,
+                super::VERSION.to_string(),
+                self.query_params,
+            ),
+        };
+        let request = match &self.project {
+            Some(project) if request.product.is_some() => request.set_host_prefix(project),
+            _ => request,
+        };
+        // 日志查询的返回较大，要求 gzip 压缩，reqwest 开启了 gzip feature，收到后自动解压
+        request
+            .set_method("POST")
+            .set_accept_encoding("gzip")
+            .set_uri(&self.uri)
+            .set_body(serde_json::Value::Object(self.body))
+    }
+}
+
+// 未设置 line 时服务端每次返回的最大行数
+const DEFAULT_LINE: u64 = 100;
+
+impl GetLogsV2 {
+    fn line(&self) -> u64 {
+        self.body
+            .get("line")
+            .and_then(|line| line.as_str()?.parse().ok())
+            .unwrap_or(DEFAULT_LINE)
+    }
+}
+
+/// 按 offset/line 翻页，返回的行数少于 line 时结束
+impl Paginated for GetLogsV2 {
+    type Item = Daum;
+
+    fn with_page(self, page: &Page) -> Self {
+        match page {
+            Page::Number(number) => {
+                let offset = (number - 1) * self.line();
+                self.set_offset(offset as i64)
+            }
+            Page::Token(_) => self,
+        }
+    }
+    fn next_page(&self, page: &Page, response: &Self::Response) -> Option<Page> {
+        match page {
+            Page::Number(number) if response.data.len() as u64 >= self.line() => {
+                Some(Page::Number(number + 1))
+            }
+            _ => None,
+        }
+    }
+    fn into_items(response: Self::Response) -> Vec<Self::Item> {
+        response.data
     }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct GetLogsV2Response {
     #[serde(rename = "meta")]
     pub meta: Meta,
@@ -152,7 +228,7 @@ pub struct GetLogsV2Response {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Meta {
     #[serde(rename = "count")]
     pub count: i64,
@@ -183,7 +259,7 @@ pub struct Meta {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Term {
     #[serde(rename = "term")]
     pub term: String,
@@ -192,7 +268,7 @@ pub struct Term {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Daum {
     #[serde(rename = "access_time")]
     pub access_time: String,
@@ -264,20 +340,168 @@ pub struct Daum {
 
 #[cfg(test)]
 mod tests {
+    use crate::aliapis::sign::api_tests;
+    use crate::aliapis::{
+        client::AliClient,
+        credential::StaticProvider,
+        transport::{
+            HttpRequest, HttpTransport, ReplayTransport, ReqwestTransport, TransportFuture,
+        },
+    };
     use crate::{aliapis::sls::get_logs_v2::GetLogsV2, Api};
+    use chrono::{DateTime, TimeZone, Utc};
+
+    fn seconds(timestamp: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(timestamp, 0).unwrap()
+    }
+
+    #[test]
+    fn accept_gzip() {
+        let request = GetLogsV2::new()
+            .set_project("demo")
+            .set_logstore("gateway")
+            .canonical_request();
+        assert_eq!(request.headers["Accept-Encoding"], "gzip");
+    }
+
+    // 把请求转发到本地的明文 HTTP 服务端
+    struct Local {
+        addr: std::net::SocketAddr,
+        inner: ReqwestTransport,
+    }
+
+    impl HttpTransport for Local {
+        fn send(&self, mut request: HttpRequest) -> TransportFuture<'_> {
+            let path = request.url.splitn(4, '/').nth(3).unwrap_or_default();
+            request.url = format!("http://{}/{}", self.addr, path);
+            self.inner.send(request)
+        }
+    }
+
+    // 声明了 Accept-Encoding: gzip 时返回 gzip 压缩的 body
+    async fn gzip_server(body: &'static str) -> std::net::SocketAddr {
+        use std::io::Write;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let n = stream.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+            assert!(request.contains("accept-encoding: gzip"));
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(body.as_bytes()).unwrap();
+            let compressed = encoder.finish().unwrap();
+            let head = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-encoding: gzip\r\n\
+                 content-length: {}\r\nconnection: close\r\n\r\n",
+                compressed.len()
+            );
+            stream.write_all(head.as_bytes()).await.unwrap();
+            stream.write_all(&compressed).await.unwrap();
+        });
+        addr
+    }
 
     #[tokio::test]
-    async fn works() -> anyhow::Result<()> {
-        let date = chrono::Local::now().timestamp();
-        let test_api = GetLogsV2::new()
-            .set_logstore("123pan-gateway")
-            .set_from(date - 3600)
-            .set_to(date);
-        //debug!("{:#?}", test_api);
-        let response = test_api.send().await?;
-        //debug!("{:#?}", response.text().await?);
-        assert_eq!(response.status(), 200);
+    async fn gzip_response() -> anyhow::Result<()> {
+        let addr = gzip_server(
+            r#"{"meta":{"count":1,"progress":"Complete"},"data":[{"__time__":"1704067230","status":"200"}]}"#,
+        )
+        .await;
+        let path =
+            std::env::temp_dir().join(format!("aliopenapi-gzip-{}.json", uuid::Uuid::new_v4()));
+        let api = || {
+            GetLogsV2::new()
+                .set_project("demo")
+                .set_logstore("gateway")
+                .set_from(seconds(1704067200))
+                .set_to(seconds(1704070800))
+        };
+        let client = |transport: ReplayTransport| {
+            AliClient::builder()
+                .credentials(StaticProvider::new("testid", "testsecret"))
+                .transport(transport)
+                .build()
+        };
+
+        // 录制时由 reqwest 解压，录制文件中保存解压后的 JSON
+        let recorder = ReplayTransport::record(
+            &path,
+            Local {
+                addr,
+                inner: ReqwestTransport::default(),
+            },
+        );
+        let response = client(recorder)?.execute_typed(api()).await?;
+        assert_eq!(response.meta.count, 1);
+        assert_eq!(response.data[0].status, "200");
+        let cassette = std::fs::read_to_string(&path)?;
+        assert!(cassette.contains(r#"\"progress\":\"Complete\""#));
+        assert!(!cassette.contains("content-encoding"));
+        assert!(!cassette.contains("body_encoding"));
 
+        let response = client(ReplayTransport::replay(&path))?
+            .execute_typed(api())
+            .await?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(response.data[0].time, "1704067230");
         Ok(())
     }
+
+    api_tests! {
+        snapshot: GetLogsV2::new()
+            .set_project("demo")
+            .set_logstore("gateway")
+            .set_from(seconds(1704067200))
+            .set_to(seconds(1704070800)),
+        canonical_request: "POST\n\
+             /logstores/gateway/logs\n\
+             \n\
+             content-type:application/json\n\
+             host:demo.cn-hangzhou.log.aliyuncs.com\n\
+             x-acs-action:GetLogsV2\n\
+             x-acs-content-sha256:011b81305edc94113631f0b695fdbfed0423fc1169ffaac084f93ad2c6f52026\n\
+             x-acs-date:2024-01-01T00:00:00Z\n\
+             x-acs-signature-nonce:3156853299f313e23d1673dc12e1703d\n\
+             x-acs-version:2020-12-30\n\
+             \n\
+             content-type;host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version\n\
+             011b81305edc94113631f0b695fdbfed0423fc1169ffaac084f93ad2c6f52026",
+        signature: "f7075c5c2ac67393dcafa030035c891a79be33e62b2a22ccb4b82de7513d1d19",
+        cassette: "sls/get_logs_v2" => GetLogsV2::new()
+            .set_project("example-gateway")
+            .set_logstore("nginx-access")
+            .set_from(seconds(1704067200))
+            .set_to(seconds(1704070800)),
+        response: |response| {
+            assert_eq!(response.meta.count, 1);
+            assert_eq!(response.meta.progress, "Complete");
+            assert!(response.meta.is_accurate);
+            let log = &response.data[0];
+            assert_eq!(log.time, "1704067230");
+            assert_eq!(log.tag_hostname, "web-01");
+            assert_eq!(log.request_uri, "/api/v1/ping");
+            assert_eq!(log.status, "200");
+            assert_eq!(log.content_type, "application/json");
+        },
+    }
+
+    #[test]
+    fn host() {
+        let request = GetLogsV2::new().set_project("demo").canonical_request();
+        assert_eq!(request.host, "demo.cn-hangzhou.log.aliyuncs.com");
+        assert_eq!(request.host_prefix.as_deref(), Some("demo"));
+        assert!(request.query_param.is_empty());
+
+        let request = GetLogsV2::new()
+            .set_project("demo")
+            .set_custom_domain("log.example.com")
+            .canonical_request();
+        assert_eq!(request.host, "log.example.com");
+        assert_eq!(request.product, None);
+    }
 }
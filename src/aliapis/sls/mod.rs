@@ -2,4 +2,4 @@ pub mod get_logs_v2;
 pub use get_logs_v2::{GetLogsV2,GetLogsV2Response};
 
 const VERSION: &str = "2020-12-30";
-const ENDPOINT: &str = "123pan-gateway.cn-hangzhou.log.aliyuncs.com";
+const PRODUCT: &str = "Sls";
@@ -0,0 +1,137 @@
+//! SM3 密码杂凑算法（GB/T 32905-2016）及 HMAC-SM3，用于 ACS3-HMAC-SM3 签名
+
+const IV: [u32; 8] = [
+    0x7380166f, 0x4914b2b9, 0x172442d7, 0xda8a0600, 0xa96f30bc, 0x163138aa, 0xe38dee4d, 0xb0fb0e4e,
+];
+
+const BLOCK_SIZE: usize = 64;
+
+fn p0(x: u32) -> u32 {
+    x ^ x.rotate_left(9) ^ x.rotate_left(17)
+}
+
+fn p1(x: u32) -> u32 {
+    x ^ x.rotate_left(15) ^ x.rotate_left(23)
+}
+
+fn compress(v: &mut [u32; 8], block: &[u8]) {
+    let mut w = [0u32; 68];
+    for (i, word) in block.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+    }
+    for j in 16..68 {
+        w[j] = p1(w[j - 16] ^ w[j - 9] ^ w[j - 3].rotate_left(15))
+            ^ w[j - 13].rotate_left(7)
+            ^ w[j - 6];
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *v;
+    for j in 0..64 {
+        let t: u32 = if j < 16 { 0x79cc4519 } else { 0x7a879d8a };
+        let ss1 = a
+            .rotate_left(12)
+            .wrapping_add(e)
+            .wrapping_add(t.rotate_left(j as u32 % 32))
+            .rotate_left(7);
+        let ss2 = ss1 ^ a.rotate_left(12);
+        let (ff, gg) = if j < 16 {
+            (a ^ b ^ c, e ^ f ^ g)
+        } else {
+            ((a & b) | (a & c) | (b & c), (e & f) | (!e & g))
+        };
+        let tt1 = ff
+            .wrapping_add(d)
+            .wrapping_add(ss2)
+            .wrapping_add(w[j] ^ w[j + 4]);
+        let tt2 = gg.wrapping_add(h).wrapping_add(ss1).wrapping_add(w[j]);
+        d = c;
+        c = b.rotate_left(9);
+        b = a;
+        a = tt1;
+        h = g;
+        g = f.rotate_left(19);
+        f = e;
+        e = p0(tt2);
+    }
+
+    for (x, y) in v.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *x ^= y;
+    }
+}
+
+/// 计算 SM3 摘要
+pub fn sm3(data: &[u8]) -> [u8; 32] {
+    let mut message = data.to_vec();
+    let bit_len = (data.len() as u64).wrapping_mul(8);
+    message.push(0x80);
+    while message.len() % BLOCK_SIZE != BLOCK_SIZE - 8 {
+        message.push(0);
+    }
+    message.extend_from_slice(&bit_len.to_be_bytes());
+
+    let mut v = IV;
+    for block in message.chunks_exact(BLOCK_SIZE) {
+        compress(&mut v, block);
+    }
+
+    let mut digest = [0u8; 32];
+    for (chunk, word) in digest.chunks_exact_mut(4).zip(v) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+/// 计算 HMAC-SM3（RFC 2104）
+pub fn hmac_sm3(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&sm3(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = block.iter().map(|b| b ^ 0x36).collect::<Vec<_>>();
+    inner.extend_from_slice(data);
+    let mut outer = block.iter().map(|b| b ^ 0x5c).collect::<Vec<_>>();
+    outer.extend_from_slice(&sm3(&inner));
+    sm3(&outer)
+}
+
+#[cfg(test)]
+mod tests {
+    use data_encoding::HEXLOWER;
+
+    use super::{hmac_sm3, sm3};
+
+    // GB/T 32905-2016 附录 A 的示例
+    #[test]
+    fn sm3_known_answer() {
+        assert_eq!(
+            HEXLOWER.encode(&sm3(b"abc")),
+            "66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0"
+        );
+        assert_eq!(
+            HEXLOWER.encode(&sm3("abcd".repeat(16).as_bytes())),
+            "debe9ff92275b8a138604889c18e5a4d6fdb70e5387e5765293dcba39c0c5732"
+        );
+        assert_eq!(
+            HEXLOWER.encode(&sm3(b"")),
+            "1ab21d8355cfa17f8e61194831e81a8f22bec8c728fefb747ed035eb5082aa2b"
+        );
+    }
+
+    #[test]
+    fn hmac_sm3_known_answer() {
+        assert_eq!(
+            HEXLOWER.encode(&hmac_sm3(
+                b"key",
+                b"The quick brown fox jumps over the lazy dog"
+            )),
+            "bd4a34077888162b210645b8ebf74b9af357303789357a27c7fc457244ebd398"
+        );
+        assert_eq!(
+            HEXLOWER.encode(&hmac_sm3(&[0xaa; 80], b"abc")),
+            "da7b7a52ef9391112a811e9f1cf2d72c5bfaaefe4f71bf2a7ace5f1a4fa37a1c"
+        );
+    }
+}
@@ -2,4 +2,7 @@ pub mod query_send_statistics;
 pub use query_send_statistics::{QuerySendStatistics,QuerySendStatisticsResponse};
 
 const VERSION: &str = "2017-05-25";
-const ENDPOINT: &str = "dysmsapi.aliyuncs.com";
+const PRODUCT: &str = "Dysmsapi";
+
+mod types;
+pub use types::*;
@@ -1,90 +1,70 @@
+use api_derive::Api;
+use chrono::NaiveDate;
 use std::fmt::Debug;
 
-use ordermap::OrderMap;
 use serde::Deserialize;
 use serde::Serialize;
 
-use crate::aliapis::sign::Api;
-use crate::aliapis::sign::RequestHeader;
+use crate::aliapis::{
+    error::ValidationError,
+    param::check_range,
+    sms::TemplateType,
+};
 
 ///查询短信发送统计详情，包括短信发送时间、短信发送成功条数、接收回执条数等
-#[derive(Debug, Clone)]
-pub struct QuerySendStatistics(OrderMap<String, String>);
-
-impl QuerySendStatistics {
+#[derive(Debug, Clone, Api)]
+#[api(
+    method = "POST",
+    body = "form",
+    default(PageIndex = 1, PageSize = 50),
+    validate_with = "check_parameters"
+)]
+pub struct QuerySendStatistics {
     ///短信发送范围。取值：
     ///1：国内短信发送记录。
     ///2：国际/港澳台短信发送记录。
-    pub fn set_is_globe(mut self, is_globe: &str) -> Self {
-        self.0.insert_sorted("IsGlobe".into(), is_globe.into());
-        self
-    }
-    ///开始日期，格式为 yyyyMMdd，例如 20181225。
-    pub fn set_start_date(mut self, start_date: &str) -> Self {
-        self.0.insert_sorted("StartDate".into(), start_date.into());
-        self
-    }
-    ///结束日期，格式为 yyyyMMdd，例如 20181225。
-    pub fn set_end_date(mut self, end_date: &str) -> Self {
-        self.0.insert_sorted("EndDate".into(), end_date.into());
-        self
-    }
+    #[api(name = "IsGlobe", required)]
+    is_globe: Option<i64>,
+    ///开始日期，只能查询 30 天内的记录。
+    #[api(name = "StartDate", required, format = "%Y%m%d")]
+    start_date: Option<NaiveDate>,
+    ///结束日期。
+    #[api(name = "EndDate", required, format = "%Y%m%d")]
+    end_date: Option<NaiveDate>,
     ///模板类型。取值：
     ///0：验证码。
     ///1：通知短信。
     ///2：推广短信。（仅支持企业客户）
     ///3：国际/港澳台消息。（仅支持企业客户）
     ///7：数字短信。
-    pub fn set_template_type(mut self, template_type: &str) -> Self {
-        self.0
-            .insert_sorted("TemplateType".into(), template_type.into());
-        self
-    }
+    #[api(name = "TemplateType")]
+    template_type: Option<TemplateType>,
     ///签名名称。
-    pub fn set_sign_name(mut self, sign_name: &str) -> Self {
-        self.0.insert_sorted("SignName".into(), sign_name.into());
-        self
-    }
+    #[api(name = "SignName")]
+    sign_name: Option<String>,
     ///当前页码。默认取值为 1。
-    pub fn set_page_index(mut self, page_index: &str) -> Self {
-        self.0.insert_sorted("PageIndex".into(), page_index.into());
-        self
-    }
+    #[api(name = "PageIndex")]
+    page_index: Option<i64>,
     ///每页显示的条数。取值范围：1~50。
-    pub fn set_page_size(mut self, page_size: &str) -> Self {
-        self.0.insert_sorted("PageSize".into(), page_size.into());
-        self
-    }
+    #[api(name = "PageSize")]
+    page_size: Option<i64>,
 }
 
-impl Api for QuerySendStatistics {
-    fn new() -> Self {
-        let mut parameters = OrderMap::new();
-        parameters.insert_sorted("PageIndex".to_string(), 1.to_string());
-        parameters.insert_sorted("PageSize".to_string(), 50.to_string());
-
-        QuerySendStatistics(parameters)
-    }
-
-    fn name(&self) -> String {
-        "QuerySendStatistics".into()
-    }
-
-    fn send(self) -> impl std::future::Future<Output = Result<reqwest::Response, reqwest::Error>> {
-        RequestHeader::new(
-            super::ENDPOINT.to_string(),
-            self.name(),
-            super::VERSION.to_string(),
-            self.0,
-        )
-        .set_method("POST")
-        .sign()
-        .send()
+impl QuerySendStatistics {
+    // 结束日期不能早于开始日期，每页最多 50 条
+    fn check_parameters(&self, error: &mut ValidationError) {
+        check_range(error, "IsGlobe", self.is_globe, 1..=2);
+        if let (Some(start), Some(end)) = (self.start_date, self.end_date) {
+            if end < start {
+                error.invalid("EndDate", "must not be earlier than StartDate");
+            }
+        }
+        check_range(error, "PageSize", self.page_size, 1..=50);
     }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct QuerySendStatisticsResponse {
     pub request_id: String,
     pub data: Data,
@@ -92,14 +72,14 @@ pub struct QuerySendStatisticsResponse {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct Data {
     pub target_list: Vec<TargetList>,
     pub total_size: i64,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[serde(rename_all = "PascalCase", default)]
 pub struct TargetList {
     pub total_count: i64,
     pub no_responded_count: i64,
@@ -110,18 +90,46 @@ pub struct TargetList {
 
 #[cfg(test)]
 mod tests {
+    use crate::aliapis::sign::api_tests;
+    use chrono::NaiveDate;
+
     use crate::aliapis::{sign::Api, sms::query_send_statistics::QuerySendStatistics};
 
-    #[tokio::test]
-    async fn works() -> anyhow::Result<()> {
-        let date = chrono::Local::now().format("%Y%m%d").to_string();
-        let test_api = QuerySendStatistics::new()
-            .set_start_date(&date)
-            .set_end_date(&date)
-            .set_is_globe("1");
-        let response = test_api.send().await?;
-        assert_eq!(response.status(), 200);
+    fn new_year() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+    }
 
-        Ok(())
+    api_tests! {
+        snapshot: QuerySendStatistics::new()
+            .set_start_date(new_year())
+            .set_end_date(new_year())
+            .set_is_globe(1),
+        canonical_request: "POST\n\
+             /\n\
+             \n\
+             content-type:application/x-www-form-urlencoded\n\
+             host:dysmsapi.aliyuncs.com\n\
+             x-acs-action:QuerySendStatistics\n\
+             x-acs-content-sha256:7535fa1dc2e3d0ce6d7efcb808ad787848d2017c61c9df5c0ae358471c2e53e5\n\
+             x-acs-date:2024-01-01T00:00:00Z\n\
+             x-acs-signature-nonce:3156853299f313e23d1673dc12e1703d\n\
+             x-acs-version:2017-05-25\n\
+             \n\
+             content-type;host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version\n\
+             7535fa1dc2e3d0ce6d7efcb808ad787848d2017c61c9df5c0ae358471c2e53e5",
+        signature: "d89b0eb38be8e2328932c73f0b99206839576296277dac81b505b18a6e415507",
+        cassette: "sms/query_send_statistics" => QuerySendStatistics::new()
+            .set_start_date(new_year())
+            .set_end_date(new_year())
+            .set_is_globe(1),
+        response: |response| {
+            assert_eq!(response.code, "OK");
+            assert_eq!(response.request_id, "4E1B8A6D-3C9F-5D20-B7E4-8F5A1C3D9E62");
+            assert_eq!(response.data.total_size, 1);
+            let target = &response.data.target_list[0];
+            assert_eq!(target.send_date, "20240101");
+            assert_eq!(target.total_count, 120);
+            assert_eq!(target.responded_success_count, 117);
+        },
     }
 }
@@ -0,0 +1,17 @@
+use crate::aliapis::param::param_enum;
+
+param_enum! {
+    /// 短信模板类型
+    pub enum TemplateType {
+        ///验证码
+        VerificationCode = "0",
+        ///通知短信
+        Notification = "1",
+        ///推广短信，仅支持企业客户
+        Promotion = "2",
+        ///国际/港澳台消息，仅支持企业客户
+        International = "3",
+        ///数字短信
+        Digital = "7",
+    }
+}
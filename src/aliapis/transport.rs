@@ -0,0 +1,491 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use data_encoding::BASE64;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Method,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tracing::debug;
+
+use crate::aliapis::error::AliError;
+
+/// 设置后 `ReplayTransport::from_env` 调用真实接口并录制，否则从录制文件回放
+pub const RECORD_ENV: &str = "ALI_CLOUD_RECORD";
+
+/// 签名后交给传输层发送的请求
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+/// 传输层返回的响应，body 已经完整读取
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    ///HTTP 状态码
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+    pub fn bytes(&self) -> &[u8] {
+        &self.body
+    }
+    ///body 按 UTF-8 解码，无效的字节会被替换
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+    ///将 body 解析为 JSON
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, AliError> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<HttpResponse, AliError>> + Send + 'a>>;
+
+/// 发送 HTTP 请求的传输层，可以替换为录制回放或测试用的实现
+pub trait HttpTransport: Send + Sync {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_>;
+}
+
+/// 基于 `reqwest::Client` 的传输层，复用连接池
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let builder = self
+                .client
+                .request(request.method, request.url)
+                .headers(request.headers)
+                .body(request.body);
+            #[cfg(debug_assertions)]
+            debug!("{:#?}", builder);
+
+            let response = builder.send().await?;
+            Ok(HttpResponse {
+                status: response.status().as_u16(),
+                headers: response.headers().clone(),
+                body: response.bytes().await?.to_vec(),
+            })
+        })
+    }
+}
+
+// 录制文件中的一次请求和响应
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    url: String,
+    #[serde(default)]
+    body: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_encoding: Option<BodyEncoding>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    body: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_encoding: Option<BodyEncoding>,
+}
+
+// body 不是 UTF-8 时的编码方式，UTF-8 的 body 保存原文
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum BodyEncoding {
+    Base64,
+}
+
+fn encode_body(body: &[u8]) -> (String, Option<BodyEncoding>) {
+    match std::str::from_utf8(body) {
+        Ok(body) => (body.to_string(), None),
+        Err(_) => (BASE64.encode(body), Some(BodyEncoding::Base64)),
+    }
+}
+
+fn decode_body(body: &str, encoding: Option<BodyEncoding>) -> Result<Vec<u8>, AliError> {
+    match encoding {
+        None => Ok(body.as_bytes().to_vec()),
+        Some(BodyEncoding::Base64) => BASE64
+            .decode(body.as_bytes())
+            .map_err(|e| AliError::Replay(format!("invalid base64 body: {}", e))),
+    }
+}
+
+// V2 签名中每次请求都会变化或包含凭证的参数，录制和匹配时去掉
+const VOLATILE_PARAMS: [&str; 5] = [
+    "AccessKeyId",
+    "SecurityToken",
+    "Signature",
+    "SignatureNonce",
+    "Timestamp",
+];
+
+impl RecordedRequest {
+    // 只保留方法、去掉易变参数的 URL 和 body，不记录 header 中的签名和凭证
+    fn from_request(request: &HttpRequest) -> Self {
+        let url = match request.url.split_once('?') {
+            Some((path, query)) => {
+                let query = query
+                    .split('&')
+                    .filter(|pair| {
+                        let key = pair.split('=').next().unwrap_or_default();
+                        !VOLATILE_PARAMS.contains(&key)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("&");
+                if query.is_empty() {
+                    path.to_string()
+                } else {
+                    format!("{}?{}", path, query)
+                }
+            }
+            None => request.url.clone(),
+        };
+        let (body, body_encoding) = encode_body(&request.body);
+        RecordedRequest {
+            method: request.method.to_string(),
+            url,
+            body,
+            body_encoding,
+        }
+    }
+}
+
+impl RecordedResponse {
+    fn from_response(response: &HttpResponse) -> Self {
+        let (body, body_encoding) = encode_body(&response.body);
+        RecordedResponse {
+            status: response.status,
+            headers: response
+                .headers
+                .iter()
+                .filter(|(name, _)| name.as_str() != "set-cookie")
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.into())))
+                .collect(),
+            body,
+            body_encoding,
+        }
+    }
+
+    fn to_response(&self) -> Result<HttpResponse, AliError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+        Ok(HttpResponse {
+            status: self.status,
+            headers,
+            body: decode_body(&self.body, self.body_encoding)?,
+        })
+    }
+}
+
+/// 录制回放传输层
+///
+/// 录制时把请求和响应保存为 JSON 文件（cassette），回放时按方法、URL 和 body 匹配录制的响应，
+/// 不访问网络。录制的内容不包含 Authorization、AK 和 STS token
+pub struct ReplayTransport {
+    path: PathBuf,
+    recorder: Option<Recorder>,
+}
+
+struct Recorder {
+    inner: Arc<dyn HttpTransport>,
+    interactions: Mutex<Vec<Interaction>>,
+}
+
+impl ReplayTransport {
+    ///从录制文件回放
+    pub fn replay(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            recorder: None,
+        }
+    }
+    ///通过 inner 发送请求，并把请求和响应录制到文件，覆盖原有内容
+    pub fn record(path: impl Into<PathBuf>, inner: impl HttpTransport + 'static) -> Self {
+        Self {
+            path: path.into(),
+            recorder: Some(Recorder {
+                inner: Arc::new(inner),
+                interactions: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+    ///设置了环境变量 `ALI_CLOUD_RECORD` 时使用 reqwest 录制，否则回放
+    pub fn from_env(path: impl Into<PathBuf>) -> Self {
+        if std::env::var_os(RECORD_ENV).is_some() {
+            Self::record(path, ReqwestTransport::default())
+        } else {
+            Self::replay(path)
+        }
+    }
+    ///录制文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    async fn load(&self) -> Result<Vec<Interaction>, AliError> {
+        let content = tokio::fs::read(&self.path).await.map_err(|e| {
+            AliError::Replay(format!("failed to read {}: {}", self.path.display(), e))
+        })?;
+        serde_json::from_slice(&content).map_err(|e| {
+            AliError::Replay(format!("failed to parse {}: {}", self.path.display(), e))
+        })
+    }
+
+    async fn save(&self, interactions: &[Interaction]) -> Result<(), AliError> {
+        let write = async {
+            if let Some(parent) = self.path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            let mut content = serde_json::to_string_pretty(interactions)?;
+            content.push('\n');
+            tokio::fs::write(&self.path, content).await
+        };
+        write.await.map_err(|e| {
+            AliError::Replay(format!("failed to write {}: {}", self.path.display(), e))
+        })
+    }
+}
+
+impl HttpTransport for ReplayTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let recorded = RecordedRequest::from_request(&request);
+            let Some(recorder) = &self.recorder else {
+                return self
+                    .load()
+                    .await?
+                    .iter()
+                    .find(|interaction| interaction.request == recorded)
+                    .ok_or_else(|| {
+                        AliError::Replay(format!(
+                            "no recorded response in {} for {} {} {}",
+                            self.path.display(),
+                            recorded.method,
+                            recorded.url,
+                            recorded.body
+                        ))
+                    })?
+                    .response
+                    .to_response();
+            };
+
+            let response = recorder.inner.send(request).await?;
+            let interactions = {
+                let mut interactions = recorder.interactions.lock().expect("recorder poisoned");
+                interactions.retain(|interaction| interaction.request != recorded);
+                interactions.push(Interaction {
+                    request: recorded,
+                    response: RecordedResponse::from_response(&response),
+                });
+                interactions.clone()
+            };
+            self.save(&interactions).await?;
+            Ok(response)
+        })
+    }
+}
+
+/// 使用 `fixtures/cassettes/{name}.json` 回放的客户端，录制时使用默认凭证链
+#[cfg(all(
+    test,
+    any(
+        feature = "dns",
+        feature = "billing",
+        feature = "sms",
+        feature = "arms",
+        feature = "sls"
+    )
+))]
+pub(crate) fn cassette_client(name: &str) -> crate::aliapis::client::AliClient {
+    use crate::aliapis::{client::AliClient, credential::StaticProvider, retry::RetryPolicy};
+
+    let path = format!(
+        "{}/fixtures/cassettes/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    let builder = AliClient::builder()
+        .retry_policy(RetryPolicy::none())
+        .transport(ReplayTransport::from_env(path));
+    let builder = if std::env::var_os(RECORD_ENV).is_some() {
+        builder
+    } else {
+        builder.credentials(StaticProvider::new("testid", "testsecret"))
+    };
+    builder.build().expect("failed to build test client")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use reqwest::{header::HeaderMap, Method};
+
+    use super::{HttpRequest, HttpResponse, HttpTransport, ReplayTransport, TransportFuture};
+    use crate::aliapis::error::AliError;
+
+    // 记录收到的请求并返回固定响应
+    struct Fixed(Mutex<Vec<String>>);
+
+    impl HttpTransport for Fixed {
+        fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+            self.0.lock().unwrap().push(request.url);
+            Box::pin(async {
+                let mut headers = HeaderMap::new();
+                headers.insert("x-acs-request-id", "1".parse().unwrap());
+                Ok(HttpResponse {
+                    status: 200,
+                    headers,
+                    body: br#"{"RequestId":"1"}"#.to_vec(),
+                })
+            })
+        }
+    }
+
+    fn request(query: &str) -> HttpRequest {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "secret".parse().unwrap());
+        HttpRequest {
+            method: Method::GET,
+            url: format!("https://ecs.aliyuncs.com/?{}", query),
+            headers,
+            body: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn record_then_replay() -> anyhow::Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("aliopenapi-cassette-{}.json", std::process::id()));
+        let recorder = ReplayTransport::record(&path, Fixed(Mutex::new(Vec::new())));
+        let response = recorder
+            .send(request(
+                "AccessKeyId=testid&Action=DescribeRegions&Signature=abc&Timestamp=1",
+            ))
+            .await?;
+        assert_eq!(response.status(), 200);
+
+        let content = std::fs::read_to_string(&path)?;
+        assert!(!content.contains("testid"));
+        assert!(!content.contains("secret"));
+        assert!(content.contains("https://ecs.aliyuncs.com/?Action=DescribeRegions"));
+
+        // 回放时忽略签名、时间等每次都会变化的参数
+        let replay = ReplayTransport::replay(&path);
+        let response = replay
+            .send(request(
+                "AccessKeyId=other&Action=DescribeRegions&Signature=def&Timestamp=2",
+            ))
+            .await?;
+        assert_eq!(response.text(), r#"{"RequestId":"1"}"#);
+        assert_eq!(response.headers()["x-acs-request-id"], "1");
+
+        let error = replay
+            .send(request("Action=DescribeInstances"))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AliError::Replay(_)));
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    // 返回非 UTF-8 的 body，例如压缩后的内容
+    struct Binary;
+
+    impl HttpTransport for Binary {
+        fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+            Box::pin(async move {
+                Ok(HttpResponse {
+                    status: 200,
+                    headers: HeaderMap::new(),
+                    body: [&[0x1f, 0x8b, 0xff][..], &request.body].concat(),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn binary_body() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "aliopenapi-cassette-binary-{}.json",
+            std::process::id()
+        ));
+        let mut binary = request("Action=PutLogs");
+        binary.body = vec![0x00, 0xfe, b'a'];
+        let recorder = ReplayTransport::record(&path, Binary);
+        recorder.send(binary.clone()).await?;
+        recorder.send(request("Action=GetLogs")).await?;
+
+        // 非 UTF-8 的 body 保存为 base64，UTF-8 的 body 保存原文
+        let content = std::fs::read_to_string(&path)?;
+        assert!(content.contains(r#""body": "AP5h","#));
+        assert!(content.contains(r#""body": "H4v/AP5h","#));
+        assert_eq!(content.matches(r#""body_encoding": "base64""#).count(), 3);
+
+        let replay = ReplayTransport::replay(&path);
+        let response = replay.send(binary).await?;
+        assert_eq!(response.bytes(), [0x1f, 0x8b, 0xff, 0x00, 0xfe, b'a']);
+        let response = replay.send(request("Action=GetLogs")).await?;
+        assert_eq!(response.bytes(), [0x1f, 0x8b, 0xff]);
+
+        // 无效的 base64 返回错误，不会替换为 U+FFFD
+        std::fs::write(&path, content.replace("H4v/AP5h", "H4v!"))?;
+        let mut binary = request("Action=PutLogs");
+        binary.body = vec![0x00, 0xfe, b'a'];
+        let error = ReplayTransport::replay(&path)
+            .send(binary)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AliError::Replay(_)), "{}", error);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}